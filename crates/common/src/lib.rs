@@ -0,0 +1,60 @@
+use std::fmt::Display;
+
+use serde::Serializer;
+
+pub use timing::RequestTimer;
+
+mod timing;
+
+/// Serializes a list as a single comma separated value, the way both the
+/// Open-Meteo and Unsplash APIs expect list query parameters.
+///
+/// Both `None` and an empty list are serialized as none so the parameter is
+/// omitted, Open-Meteo rejects an empty `hourly=` with a 400.
+pub fn csv<S: Serializer, T: Display>(
+    list: &Option<Vec<T>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match list {
+        Some(list) if !list.is_empty() => {
+            let s: String = list
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<String>>()
+                .join(",");
+
+            serializer.serialize_str(&s)
+        }
+        _ => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Options {
+        #[serde(serialize_with = "super::csv")]
+        list: Option<Vec<u32>>,
+    }
+
+    fn serialize(list: Option<Vec<u32>>) -> String {
+        serde_json::to_string(&Options { list }).unwrap()
+    }
+
+    #[test]
+    fn csv() {
+        assert_eq!(serialize(Some(vec![1, 2, 3])), r#"{"list":"1,2,3"}"#);
+        assert_eq!(serialize(Some(vec![1])), r#"{"list":"1"}"#);
+    }
+
+    #[test]
+    fn empty_is_omitted() {
+        assert_eq!(serialize(Some(vec![])), r#"{"list":null}"#);
+        assert_eq!(serialize(None), r#"{"list":null}"#);
+
+        let query = serde_urlencoded::to_string(Options { list: Some(vec![]) }).unwrap();
+        assert_eq!(query, "");
+    }
+}
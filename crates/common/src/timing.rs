@@ -0,0 +1,77 @@
+use std::{fmt::Display, future::Future};
+
+use log::debug;
+use web_time::Instant;
+
+/// Times an API request, logging its route, status, size and duration at
+/// `debug` level once it finishes.
+///
+/// With the `tracing` feature each request also gets a `request` span
+/// carrying the same fields.
+pub struct RequestTimer {
+    route: String,
+    start: Instant,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+impl RequestTimer {
+    pub fn start(route: impl Into<String>) -> Self {
+        let route = route.into();
+
+        Self {
+            #[cfg(feature = "tracing")]
+            span: tracing::debug_span!(
+                "request",
+                route = %route,
+                status = tracing::field::Empty,
+                bytes = tracing::field::Empty,
+                elapsed_ms = tracing::field::Empty,
+                error = tracing::field::Empty,
+            ),
+            route,
+            start: Instant::now(),
+        }
+    }
+
+    /// Runs `fut` inside the request's span.
+    pub fn instrument<F: Future>(&self, fut: F) -> impl Future<Output = F::Output> {
+        #[cfg(feature = "tracing")]
+        return tracing::Instrument::instrument(fut, self.span.clone());
+
+        #[cfg(not(feature = "tracing"))]
+        fut
+    }
+
+    fn elapsed_ms(&self) -> u128 {
+        self.start.elapsed().as_millis()
+    }
+
+    pub fn finish(self, status: u16, bytes: usize) {
+        let elapsed_ms = self.elapsed_ms();
+
+        #[cfg(feature = "tracing")]
+        {
+            self.span.record("status", status);
+            self.span.record("bytes", bytes);
+            self.span.record("elapsed_ms", elapsed_ms as u64);
+        }
+
+        debug!(
+            "GET {} -> {status}, {bytes} bytes in {elapsed_ms}ms",
+            self.route
+        );
+    }
+
+    pub fn fail(self, error: &impl Display) {
+        let elapsed_ms = self.elapsed_ms();
+
+        #[cfg(feature = "tracing")]
+        {
+            self.span.record("error", tracing::field::display(error));
+            self.span.record("elapsed_ms", elapsed_ms as u64);
+        }
+
+        debug!("GET {} failed after {elapsed_ms}ms: {error}", self.route);
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+
+use crate::model::{Auto, Crop, Fit, Format, PhotoFetchOptions};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    id: String,
+    width: Option<u64>,
+    height: Option<u64>,
+    format: Option<Format>,
+    crop: Option<Vec<Crop>>,
+    auto: Option<Auto>,
+    quality: Option<usize>,
+    fit: Option<Fit>,
+    dpr: Option<usize>,
+}
+
+impl CacheKey {
+    fn new(id: &str, opts: Option<&PhotoFetchOptions>) -> Self {
+        Self {
+            id: id.to_string(),
+            width: opts.and_then(|o| o.w).map(f64::to_bits),
+            height: opts.and_then(|o| o.h).map(f64::to_bits),
+            format: opts.and_then(|o| o.fm),
+            crop: opts.and_then(|o| o.crop.clone()),
+            auto: opts.and_then(|o| o.auto),
+            quality: opts.and_then(|o| o.q),
+            fit: opts.and_then(|o| o.fit),
+            dpr: opts.and_then(|o| o.dpr),
+        }
+    }
+}
+
+/// Least recently used cache of downloaded photos.
+///
+/// Entries are keyed by photo id and every fetch option, so the same photo
+/// requested at another size, quality or crop is cached separately.
+#[derive(Debug)]
+pub struct PhotoCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<CacheKey, (Bytes, u64)>,
+}
+
+impl PhotoCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub(crate) fn get(&mut self, id: &str, opts: Option<&PhotoFetchOptions>) -> Option<Bytes> {
+        self.tick += 1;
+
+        let (bytes, used) = self.entries.get_mut(&CacheKey::new(id, opts))?;
+        *used = self.tick;
+
+        Some(bytes.clone())
+    }
+
+    pub(crate) fn insert(&mut self, id: &str, opts: Option<&PhotoFetchOptions>, bytes: Bytes) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let key = CacheKey::new(id, opts);
+
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(k, _)| k.clone());
+
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        self.tick += 1;
+        self.entries.insert(key, (bytes, self.tick));
+    }
+}
@@ -1,9 +1,24 @@
+use std::time::Duration;
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("reqwest error: {0}")]
-    Reqwest(#[from] reqwest::Error),
+    Reqwest(reqwest::Error),
+    #[error("request timed out")]
+    Timeout,
     #[error("invalid API key provided")]
     InvalidAPIKey,
+    #[error("invalid API host provided: {0}")]
+    InvalidHost(String),
+    #[error("access forbidden, the resource may be private or the rate limit exceeded")]
+    Forbidden,
+    #[error("not found: {resource}")]
+    NotFound { resource: String },
+    #[error("rate limited")]
+    RateLimited {
+        /// How long the server asked to wait, if it said.
+        retry_after: Option<Duration>,
+    },
     #[error("unsplash error: {0}")]
     Unsplash(String),
     #[error("json: {0}")]
@@ -14,4 +29,14 @@ pub enum Error {
     MalformedResponse,
 }
 
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            Self::Timeout
+        } else {
+            Self::Reqwest(e)
+        }
+    }
+}
+
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -1,28 +1,187 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use bytes::Bytes;
+use fjordgard_common::RequestTimer;
+use futures_core::Stream;
+use futures_util::StreamExt;
+// tokio's timers need its runtime, which the web doesn't have
+#[cfg(target_arch = "wasm32")]
+use gloo_timers::future::sleep;
 use reqwest::{
-    Client, StatusCode,
+    Client, RequestBuilder, StatusCode, Url,
     header::{self, HeaderMap, HeaderValue},
 };
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::time::sleep;
 
+pub use cache::PhotoCache;
 pub use error::Error;
 use error::Result;
 use model::*;
+use response::UnsplashResponse;
 use serde::{Serialize, de::DeserializeOwned};
+mod cache;
 mod error;
+mod mock;
 pub mod model;
+mod response;
+
+/// Parsing of raw API payloads, for regression tests against fixtures.
+#[cfg(feature = "testing")]
+pub mod testing {
+    pub use crate::response::UnsplashResponse;
+}
 
+#[cfg(not(target_arch = "wasm32"))]
 const USER_AGENT: &str = concat!("fjordgard/", env!("CARGO_PKG_VERSION"));
-const UNSPLASH_API_HOST: &str = "https://api.unsplash.com/";
+const UNSPLASH_API_HOST: &str = "https://api.unsplash.com";
 
-#[derive(Clone)]
-pub struct UnsplashClient {
-    client: Client,
+/// How many times to retry requests failing with a 429 or 5xx status.
+///
+/// Retries back off exponentially from `base_delay`, or wait for the
+/// `Retry-After` header on rate limited responses. No retry waits longer than
+/// `max_delay`; if `Retry-After` asks for more, the request fails with
+/// [`Error::RateLimited`] instead.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
 }
 
-impl UnsplashClient {
-    pub fn new(api_key: &str) -> Result<Self> {
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn should_retry(&self, attempt: u32, status: StatusCode) -> bool {
+        attempt < self.max_retries
+            && matches!(
+                status,
+                StatusCode::TOO_MANY_REQUESTS
+                    | StatusCode::INTERNAL_SERVER_ERROR
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            )
+    }
+
+    /// How long to wait before retrying, `None` if the server asks for longer
+    /// than `max_delay`.
+    fn delay(&self, attempt: u32, headers: &HeaderMap) -> Option<Duration> {
+        match retry_after(headers) {
+            Some(delay) => (delay <= self.max_delay).then_some(delay),
+            None => Some(
+                self.base_delay
+                    .saturating_mul(2u32.saturating_pow(attempt))
+                    .min(self.max_delay),
+            ),
+        }
+    }
+}
+
+/// The wait asked for by the `Retry-After` header, in its seconds form.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+#[derive(Default)]
+pub struct UnsplashClientBuilder {
+    api_key: Option<String>,
+    api_host: Option<String>,
+    defaults: ClientDefaults,
+    retry_policy: RetryPolicy,
+    timeout: Option<Duration>,
+    #[cfg(not(target_arch = "wasm32"))]
+    connect_timeout: Option<Duration>,
+    client: Option<Client>,
+    mock: bool,
+}
+
+impl UnsplashClientBuilder {
+    pub fn api_key(mut self, api_key: &str) -> Self {
+        self.api_key = Some(api_key.to_string());
+        self
+    }
+
+    /// Override the API host, defaults to `https://api.unsplash.com`.
+    pub fn api_host(mut self, api_host: &str) -> Self {
+        self.api_host = Some(api_host.to_string());
+        self
+    }
+
+    pub fn defaults(mut self, defaults: ClientDefaults) -> Self {
+        self.defaults = defaults;
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Total time allowed for each request, failing with [`Error::Timeout`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Time allowed for establishing a connection, not supported on wasm.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Send requests through an existing client, sharing its connection
+    /// pool. The API headers are added to each request instead, and
+    /// `connect_timeout` is ignored.
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Answer every request with bundled fixtures instead of the network,
+    /// for working offline. The API key becomes optional.
+    pub fn mock(mut self, mock: bool) -> Self {
+        self.mock = mock;
+        self
+    }
+
+    pub fn build(self) -> Result<UnsplashClient> {
+        let api_host = match self.api_host {
+            Some(host) => {
+                let url = Url::parse(&host).map_err(|_| Error::InvalidHost(host.clone()))?;
+
+                if !matches!(url.scheme(), "http" | "https") || url.cannot_be_a_base() {
+                    return Err(Error::InvalidHost(host));
+                }
+
+                host.trim_end_matches('/').to_string()
+            }
+            None => UNSPLASH_API_HOST.to_string(),
+        };
+
+        let api_key = match self.api_key {
+            Some(api_key) => api_key,
+            None if self.mock => String::from("mock"),
+            None => return Err(Error::InvalidAPIKey),
+        };
+
         let mut headers = HeaderMap::new();
         headers.insert("Accept-Version", HeaderValue::from_static("v1"));
 
@@ -31,12 +190,78 @@ impl UnsplashClient {
         api_key.set_sensitive(true);
         headers.insert(header::AUTHORIZATION, api_key);
 
-        let client = Client::builder()
-            .default_headers(headers)
-            .user_agent(USER_AGENT)
-            .build()?;
+        // browsers don't allow setting the user agent
+        #[cfg(not(target_arch = "wasm32"))]
+        headers.insert(header::USER_AGENT, HeaderValue::from_static(USER_AGENT));
+
+        let client = match self.client {
+            Some(client) => client,
+            #[cfg(not(target_arch = "wasm32"))]
+            None => {
+                let mut builder = Client::builder();
+
+                if let Some(connect_timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
+                }
+
+                builder.build()?
+            }
+            #[cfg(target_arch = "wasm32")]
+            None => Client::new(),
+        };
 
-        Ok(Self { client })
+        Ok(UnsplashClient {
+            client,
+            headers,
+            api_host,
+            defaults: self.defaults,
+            retry_policy: self.retry_policy,
+            timeout: self.timeout,
+            rate_limit: Arc::new(Mutex::new(None)),
+            cache: None,
+            mock: self.mock,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct UnsplashClient {
+    client: Client,
+    /// Sent with every request, rather than set on `client` as it may be
+    /// shared.
+    headers: HeaderMap,
+    api_host: String,
+    defaults: ClientDefaults,
+    retry_policy: RetryPolicy,
+    timeout: Option<Duration>,
+    rate_limit: Arc<Mutex<Option<RateLimit>>>,
+    cache: Option<Arc<Mutex<PhotoCache>>>,
+    mock: bool,
+}
+
+impl UnsplashClient {
+    pub fn new(api_key: &str) -> Result<Self> {
+        Self::builder().api_key(api_key).build()
+    }
+
+    pub fn new_with_defaults(api_key: &str, defaults: ClientDefaults) -> Result<Self> {
+        Self::builder().api_key(api_key).defaults(defaults).build()
+    }
+
+    /// Like [`Self::new`], sharing the connection pool of `client`.
+    pub fn from_client(client: Client, api_key: &str) -> Result<Self> {
+        Self::builder().client(client).api_key(api_key).build()
+    }
+
+    pub fn builder() -> UnsplashClientBuilder {
+        UnsplashClientBuilder::default()
+    }
+
+    /// Keep up to `capacity` downloaded photos in memory, so repeated calls
+    /// to [`UnsplashClient::download_photo`] skip the network.
+    pub fn with_cache(mut self, capacity: usize) -> Self {
+        self.cache = Some(Arc::new(Mutex::new(PhotoCache::new(capacity))));
+        self
     }
 
     async fn request<Q: Serialize, T: DeserializeOwned + Debug>(
@@ -44,20 +269,94 @@ impl UnsplashClient {
         route: &str,
         query: Option<Q>,
     ) -> Result<(T, HeaderMap)> {
-        let mut req = self.client.get(format!("{UNSPLASH_API_HOST}/{route}"));
+        self.request_with(route, query, None::<()>).await
+    }
+
+    fn get<U: reqwest::IntoUrl>(&self, url: U) -> RequestBuilder {
+        let req = self.client.get(url).headers(self.headers.clone());
+
+        match self.timeout {
+            Some(timeout) => req.timeout(timeout),
+            None => req,
+        }
+    }
+
+    fn with_defaults<O: WithDefaults + Default>(&self, opt: Option<O>) -> O {
+        opt.unwrap_or_default().with_defaults(&self.defaults)
+    }
+
+    async fn request_with<Q1: Serialize, Q2: Serialize, T: DeserializeOwned + Debug>(
+        &self,
+        route: &str,
+        query: Option<Q1>,
+        extra: Option<Q2>,
+    ) -> Result<(T, HeaderMap)> {
+        let mut req = self.get(format!("{}/{route}", self.api_host));
 
         if let Some(ref query) = query {
             req = req.query(query)
         };
 
-        let res = req.send().await?;
+        if let Some(ref extra) = extra {
+            req = req.query(extra)
+        };
+
+        self.send(route, req).await
+    }
+
+    async fn send<T: DeserializeOwned + Debug>(
+        &self,
+        route: &str,
+        req: RequestBuilder,
+    ) -> Result<(T, HeaderMap)> {
+        let timer = RequestTimer::start(route);
+
+        let fetched = timer
+            .instrument(async {
+                if self.mock {
+                    let (status, headers, body) = mock::respond(route, req.build()?.url());
+
+                    return Ok((status, route.to_string(), headers, body));
+                }
+
+                let res = self.send_with_retries(req).await?;
+
+                let status = res.status();
+                let resource = res.url().path().trim_start_matches('/').to_string();
+                let headers = res.headers().clone();
+                let body = res.bytes().await?;
+
+                Ok::<_, Error>((status, resource, headers, body))
+            })
+            .await;
+
+        let (status, resource, headers, body) = match fetched {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                timer.fail(&e);
+                return Err(e);
+            }
+        };
+
+        timer.finish(status.as_u16(), body.len());
 
-        if res.status() == StatusCode::UNAUTHORIZED {
-            return Err(Error::InvalidAPIKey);
+        if let Some(rate_limit) = RateLimit::from_headers(&headers) {
+            *self.rate_limit.lock().unwrap() = Some(rate_limit);
         }
 
-        let headers = res.headers().clone();
-        let body: UnsplashResponse = res.json().await?;
+        match status {
+            StatusCode::UNAUTHORIZED => return Err(Error::InvalidAPIKey),
+            StatusCode::TOO_MANY_REQUESTS => {
+                return Err(Error::RateLimited {
+                    retry_after: retry_after(&headers),
+                });
+            }
+            StatusCode::FORBIDDEN => return Err(Error::Forbidden),
+            StatusCode::NOT_FOUND => return Err(Error::NotFound { resource }),
+            _ => {}
+        }
+
+        let body: UnsplashResponse = serde_json::from_slice(&body)?;
 
         match body {
             UnsplashResponse::Error { errors } => Err(Error::Unsplash(errors.join(", "))),
@@ -68,6 +367,35 @@ impl UnsplashClient {
         }
     }
 
+    async fn send_with_retries(&self, req: RequestBuilder) -> Result<reqwest::Response> {
+        let mut attempt = 0;
+
+        loop {
+            let Some(retry) = req.try_clone() else {
+                return Ok(req.send().await?);
+            };
+
+            let res = retry.send().await?;
+
+            if !self.retry_policy.should_retry(attempt, res.status()) {
+                return Ok(res);
+            }
+
+            // too long to keep the caller waiting
+            let Some(delay) = self.retry_policy.delay(attempt, res.headers()) else {
+                return Ok(res);
+            };
+
+            sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Rate limit reported by the most recent API response, if any.
+    pub fn last_rate_limit(&self) -> Option<RateLimit> {
+        *self.rate_limit.lock().unwrap()
+    }
+
     // Endpoint: `/collections/:id/photos`
     pub async fn collection_photos(
         &self,
@@ -75,26 +403,138 @@ impl UnsplashClient {
         opt: Option<CollectionPhotosOptions>,
     ) -> Result<CollectionPhotos> {
         let (photos, headers) = self
-            .request(&format!("collections/{id}/photos"), opt)
+            .request(
+                &format!("collections/{id}/photos"),
+                Some(self.with_defaults(opt)),
+            )
             .await?;
 
-        Ok(CollectionPhotos {
-            collection_total: headers
-                .get("X-Total")
-                .ok_or(Error::MissingHeader("X-Total"))?
-                .to_str()
-                .map_err(|_| Error::MalformedResponse)?
-                .parse::<usize>()
-                .map_err(|_| Error::MalformedResponse)?,
-            per_page: headers
-                .get("X-Per-Page")
-                .ok_or(Error::MissingHeader("X-Per-Page"))?
-                .to_str()
-                .map_err(|_| Error::MalformedResponse)?
-                .parse::<usize>()
-                .map_err(|_| Error::MalformedResponse)?,
-            photos,
-        })
+        paged(photos, &headers)
+    }
+
+    /// Endpoint: `/users/:username/photos`
+    pub async fn user_photos(
+        &self,
+        username: &str,
+        opt: Option<UserPhotosOptions>,
+    ) -> Result<CollectionPhotos> {
+        let (photos, headers) = self
+            .request(
+                &format!("users/{username}/photos"),
+                Some(self.with_defaults(opt)),
+            )
+            .await?;
+
+        paged(photos, &headers)
+    }
+
+    /// Lazily page through every photo in a collection, `per_page` at a time.
+    pub fn collection_photos_stream<'a>(
+        &'a self,
+        id: &'a str,
+        per_page: usize,
+    ) -> impl Stream<Item = Result<Photo>> + 'a {
+        async_stream::stream! {
+            let mut page = 1;
+            let mut seen = 0;
+
+            loop {
+                let res = self
+                    .collection_photos(
+                        id,
+                        Some(CollectionPhotosOptions {
+                            page: Some(page),
+                            per_page: Some(per_page),
+                            ..Default::default()
+                        }),
+                    )
+                    .await;
+
+                let photos = match res {
+                    Ok(photos) => photos,
+                    Err(e) => {
+                        yield Err(e);
+                        break;
+                    }
+                };
+
+                if photos.photos.is_empty() {
+                    break;
+                }
+
+                seen += photos.photos.len();
+
+                for photo in photos.photos {
+                    yield Ok(photo);
+                }
+
+                if seen >= photos.collection_total {
+                    break;
+                }
+
+                page += 1;
+            }
+        }
+    }
+
+    /// Endpoint: `/photos`
+    ///
+    /// Makes the smallest possible request to check the API key is accepted,
+    /// failing with [`Error::InvalidAPIKey`] if not.
+    pub async fn verify_key(&self) -> Result<()> {
+        let _: (serde_json::Value, _) = self.request("photos", Some(&[("per_page", 1)])).await?;
+
+        Ok(())
+    }
+
+    /// Endpoint: `/photos/:id`
+    pub async fn photo(&self, id: &str) -> Result<Photo> {
+        let (photo, _) = self.request(&format!("photos/{id}"), None::<()>).await?;
+
+        Ok(photo)
+    }
+
+    /// Endpoint: `/photos/random`
+    pub async fn random_photo(&self, opt: Option<RandomPhotoOptions>) -> Result<Photo> {
+        let (photo, _) = self
+            .request("photos/random", Some(self.with_defaults(opt)))
+            .await?;
+
+        Ok(photo)
+    }
+
+    /// Endpoint: `/photos/random`, returning `count` photos (max 30)
+    pub async fn random_photos(
+        &self,
+        count: usize,
+        opt: Option<RandomPhotoOptions>,
+    ) -> Result<Vec<Photo>> {
+        let (photos, _) = self
+            .request_with(
+                "photos/random",
+                Some(self.with_defaults(opt)),
+                Some(&[("count", count)]),
+            )
+            .await?;
+
+        Ok(photos)
+    }
+
+    /// Endpoint: `/search/photos`
+    pub async fn search_photos(
+        &self,
+        query: &str,
+        opt: Option<SearchPhotosOptions>,
+    ) -> Result<SearchResults<Photo>> {
+        let (results, _) = self
+            .request_with(
+                "search/photos",
+                Some(&[("query", query)]),
+                Some(self.with_defaults(opt)),
+            )
+            .await?;
+
+        Ok(results)
     }
 
     pub async fn collection(&self, id: &str) -> Result<Collection> {
@@ -105,25 +545,160 @@ impl UnsplashClient {
         Ok(collection)
     }
 
+    /// Endpoint: `/topics/:id_or_slug`
+    pub async fn topic(&self, id_or_slug: &str) -> Result<Topic> {
+        let (topic, _) = self
+            .request(&format!("topics/{id_or_slug}"), None::<()>)
+            .await?;
+
+        Ok(topic)
+    }
+
+    /// Endpoint: `/topics/:id_or_slug/photos`
+    pub async fn topic_photos(
+        &self,
+        id_or_slug: &str,
+        opt: Option<TopicPhotosOptions>,
+    ) -> Result<CollectionPhotos> {
+        let (photos, headers) = self
+            .request(
+                &format!("topics/{id_or_slug}/photos"),
+                Some(self.with_defaults(opt)),
+            )
+            .await?;
+
+        paged(photos, &headers)
+    }
+
+    /// Notify Unsplash that `photo` is being used, as required by the API
+    /// guidelines. Returns the download URL handed back by Unsplash.
+    pub async fn track_download(&self, photo: &Photo) -> Result<String> {
+        let (location, _): (DownloadLocation, _) = self
+            .send(
+                &format!("photos/{}/download", photo.id),
+                self.get(&photo.links.download_location),
+            )
+            .await?;
+
+        Ok(location.url)
+    }
+
+    /// The URL [`UnsplashClient::download_photo`] fetches `photo` from.
+    pub fn photo_download_url(photo: &Photo, opts: Option<&PhotoFetchOptions>) -> Result<String> {
+        match opts {
+            Some(opts) => opts.apply_to(&photo.urls.raw),
+            None => Ok(photo.urls.raw.clone()),
+        }
+    }
+
     pub async fn download_photo(
         &self,
         photo: &Photo,
         opts: Option<PhotoFetchOptions>,
     ) -> Result<Bytes> {
-        let mut req = self.client.get(&photo.urls.raw);
+        self.download_photo_with_progress(photo, opts, |_, _| {})
+            .await
+    }
+
+    /// Like [`Self::download_photo`], calling `progress` with the bytes
+    /// downloaded so far and the total size, if known, as chunks arrive.
+    pub async fn download_photo_with_progress(
+        &self,
+        photo: &Photo,
+        opts: Option<PhotoFetchOptions>,
+        mut progress: impl FnMut(u64, Option<u64>),
+    ) -> Result<Bytes> {
+        if let Some(ref cache) = self.cache
+            && let Some(bytes) = cache.lock().unwrap().get(&photo.id, opts.as_ref())
+        {
+            let len = bytes.len() as u64;
+            progress(len, Some(len));
+
+            return Ok(bytes);
+        }
+
+        let url = Self::photo_download_url(photo, opts.as_ref())?;
+
+        let timer = RequestTimer::start(format!("photos/{}/raw", photo.id));
+
+        let fetched = timer
+            .instrument(async {
+                if self.mock {
+                    let body = mock::download(photo)?.to_vec();
+                    progress(body.len() as u64, Some(body.len() as u64));
+
+                    return Ok((StatusCode::OK, body));
+                }
 
-        if let Some(ref query) = opts {
-            req = req.query(query);
+                let res = self.get(url).send().await?.error_for_status()?;
+                let status = res.status();
+                let total = res.content_length();
+
+                let mut body = Vec::with_capacity(total.unwrap_or_default() as usize);
+                let mut stream = res.bytes_stream();
+
+                while let Some(chunk) = stream.next().await {
+                    body.extend_from_slice(&chunk?);
+                    progress(body.len() as u64, total);
+                }
+
+                Ok::<_, Error>((status, body))
+            })
+            .await;
+
+        let body = match fetched {
+            Ok((status, body)) => {
+                timer.finish(status.as_u16(), body.len());
+                body
+            }
+            Err(e) => {
+                timer.fail(&e);
+                return Err(e);
+            }
+        };
+
+        let bytes = Bytes::from(body);
+
+        if let Some(ref cache) = self.cache {
+            cache
+                .lock()
+                .unwrap()
+                .insert(&photo.id, opts.as_ref(), bytes.clone());
         }
 
-        Ok(req.send().await?.error_for_status()?.bytes().await?)
+        Ok(bytes)
     }
 }
 
+fn header_usize(headers: &HeaderMap, name: &'static str) -> Result<usize> {
+    headers
+        .get(name)
+        .ok_or(Error::MissingHeader(name))?
+        .to_str()
+        .map_err(|_| Error::MalformedResponse)?
+        .parse::<usize>()
+        .map_err(|_| Error::MalformedResponse)
+}
+
+/// Pair a page of photos with the totals from the pagination headers.
+fn paged(photos: Vec<Photo>, headers: &HeaderMap) -> Result<CollectionPhotos> {
+    Ok(CollectionPhotos {
+        collection_total: header_usize(headers, "X-Total")?,
+        per_page: header_usize(headers, "X-Per-Page")?,
+        photos,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use std::env;
 
+    use futures_util::{StreamExt, TryStreamExt};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
     use super::*;
 
     fn api_key() -> String {
@@ -147,11 +722,655 @@ mod tests {
         assert_eq!(collection.per_page, 5);
     }
 
+    #[tokio::test]
+    async fn collection_photos_stream() {
+        let client = UnsplashClient::new(&api_key()).unwrap();
+        let photos: Vec<Photo> = client
+            .collection_photos_stream("1053828", 2)
+            .take(5)
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(photos.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn photo() {
+        let client = UnsplashClient::new(&api_key()).unwrap();
+        let collection = client
+            .collection_photos(
+                "1053828",
+                Some(CollectionPhotosOptions {
+                    per_page: Some(1),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+        let first = collection.photos.first().unwrap();
+
+        let photo = client.photo(&first.id).await.unwrap();
+
+        assert_eq!(photo.id, first.id);
+    }
+
+    #[tokio::test]
+    async fn random_photos() {
+        let client = UnsplashClient::new(&api_key()).unwrap();
+        let photos = client
+            .random_photos(
+                3,
+                Some(RandomPhotoOptions {
+                    collections: Some(vec!["1053828".to_string()]),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(photos.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn search_photos() {
+        let client = UnsplashClient::new(&api_key()).unwrap();
+        let results = client
+            .search_photos(
+                "fjord",
+                Some(SearchPhotosOptions {
+                    per_page: Some(5),
+                    orientation: Some(Orientation::Landscape),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+
+        assert!(results.results.len() <= 5);
+        assert!(results.total >= results.results.len());
+    }
+
+    #[test]
+    fn client_defaults() {
+        let client = UnsplashClient::new_with_defaults(
+            "key",
+            ClientDefaults {
+                content_filter: Some(ContentFilter::High),
+                orientation: Some(Orientation::Landscape),
+            },
+        )
+        .unwrap();
+
+        let opt = client.with_defaults::<RandomPhotoOptions>(None);
+        assert_eq!(opt.content_filter, Some(ContentFilter::High));
+        assert_eq!(opt.orientation, Some(Orientation::Landscape));
+
+        let opt = client.with_defaults(Some(RandomPhotoOptions {
+            orientation: Some(Orientation::Portrait),
+            ..Default::default()
+        }));
+        assert_eq!(opt.content_filter, Some(ContentFilter::High));
+        assert_eq!(opt.orientation, Some(Orientation::Portrait));
+    }
+
+    #[test]
+    fn blur_hash() {
+        let pixels = decode_blur_hash("LKO2?U%2Tw=w]~RBVZRi};RPxuwH", 32, 24).unwrap();
+        assert_eq!(pixels.len(), 32 * 24 * 4);
+
+        assert!(matches!(
+            decode_blur_hash("not a hash", 32, 24),
+            Err(Error::MalformedResponse)
+        ));
+    }
+
+    #[test]
+    fn photo_fetch_options_url() {
+        let base = "https://images.unsplash.com/photo-1?ixid=abc";
+
+        let opts = PhotoFetchOptions {
+            w: Some(1920.0),
+            h: Some(1080.0),
+            crop: Some(vec![Crop::Faces, Crop::Edges]),
+            ..Default::default()
+        };
+        assert_eq!(
+            opts.apply_to(base).unwrap(),
+            "https://images.unsplash.com/photo-1?ixid=abc&w=1920.0&h=1080.0&crop=faces%2Cedges"
+        );
+
+        assert_eq!(
+            PhotoFetchOptions::default()
+                .apply_to("https://images.unsplash.com/photo-1")
+                .unwrap(),
+            "https://images.unsplash.com/photo-1"
+        );
+
+        assert!(matches!(
+            opts.apply_to("not a url"),
+            Err(Error::MalformedResponse)
+        ));
+    }
+
+    #[test]
+    fn collection_photos_options_query() {
+        let opts = CollectionPhotosOptions {
+            page: Some(2),
+            per_page: Some(30),
+            order_by: Some(PhotoOrderBy::Popular),
+            orientation: Some(Orientation::Landscape),
+        };
+
+        assert_eq!(
+            serde_urlencoded::to_string(&opts).unwrap(),
+            "page=2&per_page=30&order_by=popular&orientation=landscape"
+        );
+    }
+
+    #[test]
+    fn photo_fetch_options_query() {
+        let query = |opts: &PhotoFetchOptions| serde_urlencoded::to_string(opts).unwrap();
+
+        assert_eq!(query(&PhotoFetchOptions::default()), "");
+
+        assert_eq!(
+            query(&PhotoFetchOptions {
+                w: Some(1920.0),
+                h: Some(1080.0),
+                fm: Some(Format::Png),
+                ..Default::default()
+            }),
+            "w=1920.0&h=1080.0&fm=png"
+        );
+
+        assert_eq!(
+            query(&PhotoFetchOptions {
+                crop: Some(vec![Crop::FocalPoint, Crop::Entropy]),
+                fm: Some(Format::PJpg),
+                auto: Some(Auto::Compress),
+                q: Some(60),
+                fit: Some(Fit::FillMax),
+                dpr: Some(2),
+                ..Default::default()
+            }),
+            "crop=focalpoint%2Centropy&fm=pjpg&auto=compress&q=60&fit=fillmax&dpr=2"
+        );
+
+        // an empty crop list is left out rather than sent as `crop=`
+        assert_eq!(
+            query(&PhotoFetchOptions {
+                crop: Some(vec![]),
+                fm: Some(Format::BlurHash),
+                ..Default::default()
+            }),
+            "fm=blurhash"
+        );
+    }
+
+    const PHOTO: &str = r##"{
+        "id": "abc", "slug": "a-fjord-abc", "alternative_slugs": {},
+        "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z",
+        "promoted_at": null, "width": 6000, "height": 4000, "color": "#0c2640",
+        "blur_hash": "LKO2?U%2Tw=w]~RBVZRi};RPxuwH", "description": null,
+        "alt_description": null,
+        "urls": {
+            "raw": "https://images.unsplash.com/photo-1", "full": "", "regular": "",
+            "small": "", "thumb": "", "small_s3": ""
+        },
+        "links": {
+            "self": "", "html": "https://unsplash.com/photos/a-fjord-abc",
+            "download": "", "download_location": ""
+        },
+        "likes": 0, "liked_by_user": false, "topic_submissions": {},
+        "asset_type": "photo",
+        "user": {
+            "id": "u", "updated_at": "2024-01-01T00:00:00Z", "username": "someone",
+            "first_name": "Some", "last_name": "One", "twitter_username": null,
+            "portfolio_url": null, "bio": null, "location": null,
+            "links": {
+                "self": "", "html": "https://unsplash.com/@someone", "photos": "",
+                "likes": "", "portfolio": "", "following": null, "followers": null
+            },
+            "profile_image": { "small": "", "medium": "", "large": "" },
+            "instagram_username": null, "total_collections": 0, "total_likes": 0,
+            "total_photos": 0, "total_promoted_photos": 0, "total_illustrations": 0,
+            "total_promoted_illustrations": 0, "accepted_tos": true, "for_hire": false,
+            "social": {
+                "instagram_username": null, "portfolio_url": null,
+                "twitter_username": null, "paypal_email": null
+            }
+        }
+    }"##;
+
+    #[test]
+    fn attribution() {
+        let mut photo: Photo = serde_json::from_str(PHOTO).unwrap();
+
+        assert_eq!(
+            photo.attribution(),
+            Attribution {
+                photo_url: "https://unsplash.com/photos/a-fjord-abc?utm_source=fjordgard&utm_medium=referral".to_string(),
+                author_name: "Some One".to_string(),
+                author_url: "https://unsplash.com/@someone?utm_source=fjordgard&utm_medium=referral".to_string(),
+                unsplash_url: "https://unsplash.com/?utm_source=fjordgard&utm_medium=referral".to_string(),
+            }
+        );
+
+        photo.user.last_name = None;
+        photo.links.html.push_str("?lang=en");
+
+        let attribution = photo.attribution();
+        assert_eq!(attribution.author_name, "Some");
+        assert_eq!(
+            attribution.photo_url,
+            "https://unsplash.com/photos/a-fjord-abc?lang=en&utm_source=fjordgard&utm_medium=referral"
+        );
+    }
+
+    #[test]
+    fn photo_dimensions_and_color() {
+        let mut photo: Photo = serde_json::from_str(PHOTO).unwrap();
+
+        assert_eq!(photo.aspect_ratio(), 1.5);
+        assert_eq!(photo.average_color(), Some([0x0c, 0x26, 0x40]));
+
+        photo.height = 0;
+        assert_eq!(photo.aspect_ratio(), 1.0);
+
+        for color in ["0c2640", "#0c26", "#0c264g", "#0c2640ff"] {
+            photo.color = color.to_string();
+            assert_eq!(photo.average_color(), None);
+        }
+    }
+
+    #[test]
+    fn photo_cache() {
+        let small = PhotoFetchOptions {
+            w: Some(100.0),
+            h: Some(100.0),
+            fm: Some(Format::Png),
+            ..Default::default()
+        };
+
+        let mut cache = PhotoCache::new(2);
+        cache.insert("a", Some(&small), Bytes::from_static(b"a-small"));
+        cache.insert("a", None, Bytes::from_static(b"a-raw"));
+
+        assert_eq!(
+            cache.get("a", Some(&small)),
+            Some(Bytes::from_static(b"a-small"))
+        );
+        assert_eq!(cache.get("b", None), None);
+
+        // "a" at its raw size is now the least recently used
+        cache.insert("b", None, Bytes::from_static(b"b-raw"));
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("a", None), None);
+        assert!(cache.get("a", Some(&small)).is_some());
+        assert!(cache.get("b", None).is_some());
+
+        let low_quality = PhotoFetchOptions {
+            q: Some(10),
+            ..small
+        };
+        assert_eq!(cache.get("a", Some(&low_quality)), None);
+
+        let mut disabled = PhotoCache::new(0);
+        disabled.insert("a", None, Bytes::from_static(b"a-raw"));
+        assert!(disabled.is_empty());
+    }
+
+    #[test]
+    fn api_host() {
+        let client = UnsplashClient::builder().api_key("key").build().unwrap();
+        assert_eq!(client.api_host, "https://api.unsplash.com");
+
+        let client = UnsplashClient::builder()
+            .api_key("key")
+            .api_host("http://localhost:8080/")
+            .build()
+            .unwrap();
+        assert_eq!(client.api_host, "http://localhost:8080");
+
+        for host in ["localhost:8080", "not a url", "ftp://example.com"] {
+            assert!(matches!(
+                UnsplashClient::builder()
+                    .api_key("key")
+                    .api_host(host)
+                    .build(),
+                Err(Error::InvalidHost(_))
+            ));
+        }
+
+        assert!(matches!(
+            UnsplashClient::builder().build(),
+            Err(Error::InvalidAPIKey)
+        ));
+    }
+
+    /// Serve each response in order, one per connection.
+    async fn mock_server(responses: Vec<(u16, &'static str)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+
+                let mut buf = vec![0; 4096];
+                let mut read = 0;
+                while !buf[..read].windows(4).any(|w| w == b"\r\n\r\n") {
+                    read += stream.read(&mut buf[read..]).await.unwrap();
+                }
+
+                let response = format!(
+                    "HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nX-Total: 0\r\nX-Per-Page: 10\r\n\
+                     Connection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        format!("http://{addr}")
+    }
+
+    #[tokio::test]
+    async fn user_photos() {
+        let host = mock_server(vec![(200, "[]")]).await;
+        let client = UnsplashClient::builder()
+            .api_key("key")
+            .api_host(&host)
+            .build()
+            .unwrap();
+
+        let photos = client
+            .user_photos(
+                "someone",
+                Some(UserPhotosOptions {
+                    order_by: Some(PhotoOrderBy::Popular),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(photos.collection_total, 0);
+        assert_eq!(photos.per_page, 10);
+        assert!(photos.photos.is_empty());
+    }
+
+    #[tokio::test]
+    async fn shared_client() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // only accepts requests carrying the API headers
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+
+            let mut buf = vec![0; 4096];
+            let mut read = 0;
+            while !buf[..read].windows(4).any(|w| w == b"\r\n\r\n") {
+                read += stream.read(&mut buf[read..]).await.unwrap();
+            }
+
+            let request = String::from_utf8_lossy(&buf[..read]).to_lowercase();
+            let status = if request.contains("authorization: client-id key")
+                && request.contains("accept-version: v1")
+            {
+                200
+            } else {
+                401
+            };
+
+            let response = format!(
+                "HTTP/1.1 {status} Mock\r\nContent-Length: 2\r\nConnection: close\r\n\r\n[]"
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let client = UnsplashClient::builder()
+            .client(Client::new())
+            .api_key("key")
+            .api_host(&format!("http://{addr}"))
+            .build()
+            .unwrap();
+
+        client.verify_key().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn verify_key() {
+        let host = mock_server(vec![
+            (200, "[]"),
+            (
+                401,
+                r#"{"errors":["OAuth error: The access token is invalid"]}"#,
+            ),
+        ])
+        .await;
+        let client = UnsplashClient::builder()
+            .api_key("key")
+            .api_host(&host)
+            .build()
+            .unwrap();
+
+        client.verify_key().await.unwrap();
+        assert!(matches!(
+            client.verify_key().await,
+            Err(Error::InvalidAPIKey)
+        ));
+    }
+
+    #[tokio::test]
+    async fn download_progress() {
+        let host = mock_server(vec![(200, "fjord")]).await;
+        let client = UnsplashClient::builder().api_key("key").build().unwrap();
+
+        let mut photo: Photo = serde_json::from_str(PHOTO).unwrap();
+        photo.urls.raw = format!("{host}/photo-1");
+
+        let mut reported = Vec::new();
+        let bytes = client
+            .download_photo_with_progress(&photo, None, |downloaded, total| {
+                reported.push((downloaded, total))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(bytes, Bytes::from_static(b"fjord"));
+        assert_eq!(reported.last(), Some(&(5, Some(5))));
+    }
+
+    #[tokio::test]
+    async fn mock() {
+        let client = UnsplashClient::builder().mock(true).build().unwrap();
+
+        let collection = client.collection("any").await.unwrap();
+        assert!(collection.is_accessible());
+
+        let page = client.collection_photos("any", None).await.unwrap();
+        assert_eq!(page.collection_total, collection.total_photos);
+        assert_eq!(page.photos.len(), collection.total_photos);
+
+        let topic = client.topic("wallpapers").await.unwrap();
+        assert_eq!(topic.total_photos, 3);
+
+        let photos = client.random_photos(5, None).await.unwrap();
+        assert_eq!(photos.len(), 5);
+
+        let photo = client.photo(&photos[0].id).await.unwrap();
+        assert_eq!(client.track_download(&photo).await.unwrap(), photo.urls.raw);
+        assert!(matches!(
+            client.photo("missing").await,
+            Err(Error::NotFound { .. })
+        ));
+
+        let bytes = client.download_photo(&photo, None).await.unwrap();
+        assert!(bytes.starts_with(b"BM"));
+    }
+
+    #[tokio::test]
+    async fn topic() {
+        let host = mock_server(vec![
+            (
+                200,
+                r#"{"id":"bo8jQKTaE0Y","slug":"wallpapers","title":"Wallpapers","description":null,"total_photos":42,"cover_photo":null}"#,
+            ),
+            (200, "[]"),
+        ])
+        .await;
+        let client = UnsplashClient::builder()
+            .api_key("key")
+            .api_host(&host)
+            .build()
+            .unwrap();
+
+        let topic = client.topic("wallpapers").await.unwrap();
+        assert_eq!(topic.slug, "wallpapers");
+        assert_eq!(topic.total_photos, 42);
+
+        let photos = client.topic_photos(&topic.slug, None).await.unwrap();
+        assert!(photos.photos.is_empty());
+    }
+
+    #[tokio::test]
+    async fn timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("http://{}", listener.local_addr().unwrap());
+
+        // accept the connection but never respond
+        tokio::spawn(async move {
+            let (_stream, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(10)).await;
+        });
+
+        let client = UnsplashClient::builder()
+            .api_key("key")
+            .api_host(&host)
+            .timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+
+        let err = client.photo("abc").await.unwrap_err();
+        assert!(matches!(err, Error::Timeout));
+    }
+
+    #[tokio::test]
+    async fn retries() {
+        let host = mock_server(vec![(503, ""), (503, ""), (200, "[]")]).await;
+        let client = UnsplashClient::builder()
+            .api_key("key")
+            .api_host(&host)
+            .retry_policy(RetryPolicy {
+                max_retries: 2,
+                base_delay: Duration::from_millis(1),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+
+        let photos = client.collection_photos("1", None).await.unwrap();
+        assert!(photos.photos.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rate_limited() {
+        let host = mock_server(vec![(429, "Rate Limit Exceeded")]).await;
+        let client = UnsplashClient::builder()
+            .api_key("key")
+            .api_host(&host)
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            client.collection("1").await,
+            Err(Error::RateLimited { retry_after: None })
+        ));
+    }
+
+    #[test]
+    fn retry_delay_capped() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(10),
+        };
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(policy.delay(1, &headers), Some(Duration::from_secs(2)));
+        assert_eq!(policy.delay(8, &headers), Some(Duration::from_secs(10)));
+
+        headers.insert(header::RETRY_AFTER, HeaderValue::from_static("5"));
+        assert_eq!(policy.delay(0, &headers), Some(Duration::from_secs(5)));
+
+        headers.insert(header::RETRY_AFTER, HeaderValue::from_static("3600"));
+        assert_eq!(policy.delay(0, &headers), None);
+    }
+
+    #[tokio::test]
+    async fn no_retries_by_default() {
+        let host = mock_server(vec![(503, "")]).await;
+        let client = UnsplashClient::builder()
+            .api_key("key")
+            .api_host(&host)
+            .build()
+            .unwrap();
+
+        assert!(client.collection_photos("1", None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn status_errors() {
+        let host = mock_server(vec![
+            (403, r#"{"errors":["Rate Limit Exceeded"]}"#),
+            (404, r#"{"errors":["Couldn't find Collection"]}"#),
+        ])
+        .await;
+        let client = UnsplashClient::builder()
+            .api_key("key")
+            .api_host(&host)
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            client.collection("1").await,
+            Err(Error::Forbidden)
+        ));
+        assert!(matches!(
+            client.collection("1").await,
+            Err(Error::NotFound { resource }) if resource == "collections/1"
+        ));
+    }
+
+    #[test]
+    fn rate_limit_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(RateLimit::from_headers(&headers), None);
+
+        headers.insert("X-Ratelimit-Limit", HeaderValue::from_static("50"));
+        assert_eq!(RateLimit::from_headers(&headers), None);
+
+        headers.insert("X-Ratelimit-Remaining", HeaderValue::from_static("49"));
+        assert_eq!(
+            RateLimit::from_headers(&headers),
+            Some(RateLimit {
+                limit: 50,
+                remaining: 49
+            })
+        );
+    }
+
     #[tokio::test]
     async fn collection() {
         let client = UnsplashClient::new(&api_key()).unwrap();
         let collection = client.collection("1053828").await.unwrap();
 
         assert_eq!(collection.title, "Tabliss Official");
+        assert!(collection.is_accessible());
     }
 }
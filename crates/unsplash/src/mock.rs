@@ -0,0 +1,126 @@
+//! Canned responses served by clients built with
+//! [`UnsplashClientBuilder::mock`](crate::UnsplashClientBuilder::mock), so
+//! the app can run without an API key or network.
+
+use bytes::Bytes;
+use reqwest::{StatusCode, Url, header::HeaderMap};
+use serde_json::{Value, json};
+
+use crate::{error::Result, model::Photo};
+
+const PHOTOS: &str = include_str!("../fixtures/photos.json");
+const COLLECTION: &str = include_str!("../fixtures/collection.json");
+const TOPIC: &str = include_str!("../fixtures/topic.json");
+
+/// Unsplash's default page size.
+const DEFAULT_PER_PAGE: usize = 10;
+/// Width of the images served in place of photo downloads.
+const DOWNLOAD_WIDTH: u32 = 320;
+
+/// The status, headers and body the API would answer `route` with.
+pub(crate) fn respond(route: &str, url: &Url) -> (StatusCode, HeaderMap, Bytes) {
+    let query = |name: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == name)
+            .and_then(|(_, v)| v.parse::<usize>().ok())
+    };
+
+    let photos: Vec<Value> = serde_json::from_str(PHOTOS).expect("photos fixture");
+    let find = |id: &str| photos.iter().find(|p| p["id"] == id).cloned();
+
+    let mut headers = HeaderMap::new();
+    headers.insert("X-Ratelimit-Limit", 50.into());
+    headers.insert("X-Ratelimit-Remaining", 50.into());
+
+    let segments: Vec<&str> = route.split('/').collect();
+
+    let body = match segments.as_slice() {
+        ["photos"] | ["collections" | "topics" | "users", _, "photos"] => {
+            let per_page = query("per_page").unwrap_or(DEFAULT_PER_PAGE);
+
+            headers.insert("X-Total", photos.len().into());
+            headers.insert("X-Per-Page", per_page.into());
+
+            // every photo fits on the first page
+            match query("page") {
+                Some(page) if page > 1 => Some(json!([])),
+                _ => Some(Value::Array(
+                    photos.iter().take(per_page).cloned().collect(),
+                )),
+            }
+        }
+        ["photos", "random"] => match query("count") {
+            Some(count) => Some(Value::Array(
+                photos.iter().cycle().take(count).cloned().collect(),
+            )),
+            None => photos.first().cloned(),
+        },
+        ["photos", id, "download"] => find(id).map(|p| json!({ "url": p["urls"]["raw"] })),
+        ["photos", id] => find(id),
+        ["search", "photos"] => Some(json!({
+            "total": photos.len(),
+            "total_pages": 1,
+            "results": photos,
+        })),
+        ["collections", _] => serde_json::from_str(COLLECTION).ok(),
+        ["topics", _] => serde_json::from_str(TOPIC).ok(),
+        _ => None,
+    };
+
+    match body {
+        Some(body) => (StatusCode::OK, headers, Bytes::from(body.to_string())),
+        None => (
+            StatusCode::NOT_FOUND,
+            headers,
+            Bytes::from_static(br#"{"errors":["Couldn't find resource"]}"#),
+        ),
+    }
+}
+
+/// The photo's BlurHash as a BMP, standing in for the real image.
+pub(crate) fn download(photo: &Photo) -> Result<Bytes> {
+    let width = DOWNLOAD_WIDTH;
+    let height = ((width as f64 / photo.aspect_ratio()).round() as u32).max(1);
+    let pixels = photo.blur_placeholder(width, height)?;
+
+    Ok(bmp(width, height, &pixels))
+}
+
+/// Encode RGBA pixels as an uncompressed 24-bit BMP, about the simplest
+/// format every decoder supports.
+fn bmp(width: u32, height: u32, rgba: &[u8]) -> Bytes {
+    const HEADER_SIZE: u32 = 54;
+
+    // rows are padded to 4 bytes
+    let row_size = (width * 3).next_multiple_of(4);
+    let file_size = HEADER_SIZE + row_size * height;
+
+    let mut out = Vec::with_capacity(file_size as usize);
+
+    // file header
+    out.extend_from_slice(b"BM");
+    out.extend_from_slice(&file_size.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out.extend_from_slice(&HEADER_SIZE.to_le_bytes());
+
+    // info header, a negative height stores rows top to bottom
+    out.extend_from_slice(&40u32.to_le_bytes());
+    out.extend_from_slice(&(width as i32).to_le_bytes());
+    out.extend_from_slice(&(-(height as i32)).to_le_bytes());
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&24u16.to_le_bytes());
+    // no compression, then sizes and palette left for the decoder to work out
+    out.extend_from_slice(&[0; 24]);
+
+    for row in rgba.chunks_exact(width as usize * 4) {
+        let start = out.len();
+
+        for pixel in row.chunks_exact(4) {
+            out.extend_from_slice(&[pixel[2], pixel[1], pixel[0]]);
+        }
+
+        out.resize(start + row_size as usize, 0);
+    }
+
+    Bytes::from(out)
+}
@@ -1,29 +1,183 @@
-use std::{collections::HashMap, fmt::Display};
+use std::collections::HashMap;
 
-use serde::{Deserialize, Serialize, Serializer};
+use reqwest::{Url, header::HeaderMap};
+use serde::{Deserialize, Serialize};
 use strum::Display;
 
-#[derive(Deserialize, Debug)]
-#[serde(untagged)]
-pub(crate) enum UnsplashResponse {
-    Error { errors: Vec<String> },
-    Success(serde_json::Value),
+use crate::{Error, error::Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub limit: usize,
+    pub remaining: usize,
 }
 
-#[derive(Serialize)]
-#[serde(rename = "lowercase")]
+impl RateLimit {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let get = |name: &str| headers.get(name)?.to_str().ok()?.parse::<usize>().ok();
+
+        Some(Self {
+            limit: get("X-Ratelimit-Limit")?,
+            remaining: get("X-Ratelimit-Remaining")?,
+        })
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
 pub enum Orientation {
     Landscape,
     Portrait,
     Squarish,
 }
 
+/// Collections only support ordering by `latest`, `oldest` and `popular`.
 #[serde_with::skip_serializing_none]
 #[derive(Serialize, Default)]
 pub struct CollectionPhotosOptions {
     pub page: Option<usize>,
     pub per_page: Option<usize>,
+    pub order_by: Option<PhotoOrderBy>,
+    pub orientation: Option<Orientation>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PhotoOrderBy {
+    Latest,
+    Oldest,
+    Popular,
+    Views,
+    Downloads,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Default)]
+pub struct UserPhotosOptions {
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+    pub order_by: Option<PhotoOrderBy>,
+    pub orientation: Option<Orientation>,
+}
+
+/// Topics only support ordering by `latest`, `oldest` and `popular`.
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Default)]
+pub struct TopicPhotosOptions {
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+    pub order_by: Option<PhotoOrderBy>,
+    pub orientation: Option<Orientation>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentFilter {
+    Low,
+    High,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Default)]
+pub struct RandomPhotoOptions {
+    #[serde(serialize_with = "fjordgard_common::csv")]
+    pub collections: Option<Vec<String>>,
+    #[serde(serialize_with = "fjordgard_common::csv")]
+    pub topics: Option<Vec<String>>,
+    pub username: Option<String>,
+    pub query: Option<String>,
+    pub orientation: Option<Orientation>,
+    pub content_filter: Option<ContentFilter>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchOrderBy {
+    Relevant,
+    Latest,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PhotoColor {
+    BlackAndWhite,
+    Black,
+    White,
+    Yellow,
+    Orange,
+    Red,
+    Purple,
+    Magenta,
+    Green,
+    Teal,
+    Blue,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Default)]
+pub struct SearchPhotosOptions {
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+    pub order_by: Option<SearchOrderBy>,
     pub orientation: Option<Orientation>,
+    pub color: Option<PhotoColor>,
+    pub content_filter: Option<ContentFilter>,
+}
+
+/// Options merged into every request made by a client, only filling in fields
+/// left unset by the caller.
+#[derive(Default, Debug, Clone)]
+pub struct ClientDefaults {
+    pub content_filter: Option<ContentFilter>,
+    pub orientation: Option<Orientation>,
+}
+
+pub(crate) trait WithDefaults {
+    fn with_defaults(self, defaults: &ClientDefaults) -> Self;
+}
+
+impl WithDefaults for CollectionPhotosOptions {
+    fn with_defaults(mut self, defaults: &ClientDefaults) -> Self {
+        self.orientation = self.orientation.or(defaults.orientation);
+        self
+    }
+}
+
+impl WithDefaults for UserPhotosOptions {
+    fn with_defaults(mut self, defaults: &ClientDefaults) -> Self {
+        self.orientation = self.orientation.or(defaults.orientation);
+        self
+    }
+}
+
+impl WithDefaults for TopicPhotosOptions {
+    fn with_defaults(mut self, defaults: &ClientDefaults) -> Self {
+        self.orientation = self.orientation.or(defaults.orientation);
+        self
+    }
+}
+
+impl WithDefaults for RandomPhotoOptions {
+    fn with_defaults(mut self, defaults: &ClientDefaults) -> Self {
+        self.orientation = self.orientation.or(defaults.orientation);
+        self.content_filter = self.content_filter.or(defaults.content_filter);
+        self
+    }
+}
+
+impl WithDefaults for SearchPhotosOptions {
+    fn with_defaults(mut self, defaults: &ClientDefaults) -> Self {
+        self.orientation = self.orientation.or(defaults.orientation);
+        self.content_filter = self.content_filter.or(defaults.content_filter);
+        self
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SearchResults<T> {
+    pub total: usize,
+    pub total_pages: usize,
+    pub results: Vec<T>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -56,6 +210,77 @@ pub struct Photo {
     pub user: User,
 }
 
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct DownloadLocation {
+    pub(crate) url: String,
+}
+
+impl Photo {
+    /// Decode the photo's BlurHash into `width * height` RGBA pixels.
+    pub fn blur_placeholder(&self, width: u32, height: u32) -> Result<Vec<u8>> {
+        decode_blur_hash(&self.blur_hash, width, height)
+    }
+
+    /// Width divided by height, 1.0 if the dimensions are missing.
+    pub fn aspect_ratio(&self) -> f64 {
+        if self.width == 0 || self.height == 0 {
+            return 1.0;
+        }
+
+        self.width as f64 / self.height as f64
+    }
+
+    /// The photo's average color as RGB, parsed from its `#rrggbb` hex code.
+    pub fn average_color(&self) -> Option<[u8; 3]> {
+        let hex = self.color.strip_prefix('#')?;
+
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+
+        Some([channel(0)?, channel(2)?, channel(4)?])
+    }
+
+    /// Links crediting the photo, its author and Unsplash, as required by the
+    /// API guidelines.
+    pub fn attribution(&self) -> Attribution {
+        let author_name = match &self.user.last_name {
+            Some(last) => format!("{} {last}", self.user.first_name),
+            None => self.user.first_name.clone(),
+        };
+
+        Attribution {
+            photo_url: with_referral(&self.links.html),
+            author_name,
+            author_url: with_referral(&self.user.links.html),
+            unsplash_url: with_referral("https://unsplash.com/"),
+        }
+    }
+}
+
+/// Query identifying fjordgard as the referrer of attribution links.
+const REFERRAL: &str = "utm_source=fjordgard&utm_medium=referral";
+
+fn with_referral(url: &str) -> String {
+    let separator = if url.contains('?') { '&' } else { '?' };
+    format!("{url}{separator}{REFERRAL}")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribution {
+    pub photo_url: String,
+    pub author_name: String,
+    pub author_url: String,
+    pub unsplash_url: String,
+}
+
+/// Decode a BlurHash into `width * height` RGBA pixels.
+pub fn decode_blur_hash(hash: &str, width: u32, height: u32) -> Result<Vec<u8>> {
+    blurhash::decode(hash, width, height, 1.0).map_err(|_| Error::MalformedResponse)
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct PhotoUrls {
     pub raw: String,
@@ -134,7 +359,7 @@ pub struct UserSocials {
     pub paypal_email: Option<String>,
 }
 
-#[derive(Display)]
+#[derive(Display, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[strum(serialize_all = "lowercase")]
 pub enum Crop {
     Top,
@@ -147,8 +372,8 @@ pub enum Crop {
     Entropy,
 }
 
-#[derive(Serialize)]
-#[serde(rename = "lowercase")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
 pub enum Format {
     Avif,
     Gif,
@@ -166,8 +391,8 @@ pub enum Format {
     BlurHash,
 }
 
-#[derive(Serialize)]
-#[serde(rename = "lowercase")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
 pub enum Auto {
     Compress,
     Enhance,
@@ -176,8 +401,8 @@ pub enum Auto {
     Redeye,
 }
 
-#[derive(Serialize)]
-#[serde(rename = "lowercase")]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
 pub enum Fit {
     Clamp,
     Clip,
@@ -195,7 +420,7 @@ pub enum Fit {
 pub struct PhotoFetchOptions {
     pub w: Option<f64>,
     pub h: Option<f64>,
-    #[serde(serialize_with = "csv")]
+    #[serde(serialize_with = "fjordgard_common::csv")]
     pub crop: Option<Vec<Crop>>,
     pub fm: Option<Format>,
     pub auto: Option<Auto>,
@@ -204,20 +429,38 @@ pub struct PhotoFetchOptions {
     pub dpr: Option<usize>,
 }
 
-fn csv<S: Serializer, T: Display>(list: &Option<Vec<T>>, serializer: S) -> Result<S::Ok, S::Error> {
-    if let Some(list) = list {
-        let s: String = list
-            .iter()
-            .map(|v| v.to_string())
-            .collect::<Vec<String>>()
-            .join(",");
+impl PhotoFetchOptions {
+    /// Append the options to `base` as query parameters, as a request for the
+    /// photo would.
+    pub fn apply_to(&self, base: &str) -> Result<String> {
+        let mut url = Url::parse(base).map_err(|_| Error::MalformedResponse)?;
+
+        {
+            let mut pairs = url.query_pairs_mut();
+            self.serialize(serde_urlencoded::Serializer::new(&mut pairs))
+                .map_err(|_| Error::MalformedResponse)?;
+        }
+
+        // an empty query leaves a trailing `?` behind
+        if url.query() == Some("") {
+            url.set_query(None);
+        }
 
-        serializer.serialize_str(&s)
-    } else {
-        serializer.serialize_none()
+        Ok(url.into())
     }
 }
 
+/// A curated feed of photos, such as `nature` or `wallpapers`.
+#[derive(Deserialize, Debug, Clone)]
+pub struct Topic {
+    pub id: String,
+    pub slug: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub total_photos: usize,
+    pub cover_photo: Option<Photo>,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct CollectionLinks {
     #[serde(rename = "self")]
@@ -238,6 +481,13 @@ pub struct PreviewPhoto {
     pub urls: PhotoUrls,
 }
 
+impl PreviewPhoto {
+    /// Decode the photo's BlurHash into `width * height` RGBA pixels.
+    pub fn blur_placeholder(&self, width: u32, height: u32) -> Result<Vec<u8>> {
+        decode_blur_hash(&self.blur_hash, width, height)
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct Collection {
     pub id: String,
@@ -255,3 +505,11 @@ pub struct Collection {
     pub cover_photo: Option<Photo>,
     pub preview_photos: Vec<PreviewPhoto>,
 }
+
+impl Collection {
+    /// Whether photos can be listed from the collection, it's neither
+    /// private nor empty.
+    pub fn is_accessible(&self) -> bool {
+        !self.private && self.total_photos > 0
+    }
+}
@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+/// Body of every Unsplash API response, errors come as a list of messages
+/// rather than the requested data.
+///
+/// Public only within the crate, unless re-exported by the `testing` feature.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum UnsplashResponse {
+    Error { errors: Vec<String> },
+    Success(serde_json::Value),
+}
@@ -1,13 +1,108 @@
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("reqwest error: {0}")]
-    Reqwest(#[from] reqwest::Error),
-    #[error("meteo error: {0}")]
-    Meteo(String),
+    Reqwest(reqwest::Error),
+    #[error("request timed out")]
+    Timeout,
+    #[error("meteo error: {reason}")]
+    Meteo {
+        reason: String,
+        kind: MeteoErrorKind,
+    },
+    /// Non-success response without an Open-Meteo error body, such as a
+    /// gateway error page.
+    #[error("weather service unavailable ({status})")]
+    Http { status: u16 },
     #[error("json: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error("failed to parse pressure level")]
     InvalidPressureLevel,
+    #[error("no forecast available")]
+    Unavailable,
+    #[error("invalid options: {0}")]
+    InvalidOptions(&'static str),
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            Self::Timeout
+        } else {
+            Self::Reqwest(e)
+        }
+    }
+}
+
+impl Error {
+    /// Whether the failure is likely to go away on its own (network issues,
+    /// server errors), as opposed to a problem with the request itself.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Reqwest(e) => {
+                #[cfg(not(target_arch = "wasm32"))]
+                let connect = e.is_connect();
+                #[cfg(target_arch = "wasm32")]
+                let connect = e.is_request();
+
+                e.is_timeout() || connect || e.status().is_some_and(|s| s.is_server_error())
+            }
+            Self::Meteo { kind, .. } => *kind == MeteoErrorKind::RateLimited,
+            Self::Http { status } => *status >= 500,
+            Self::Timeout | Self::Unavailable => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(
+            self,
+            Self::Meteo {
+                kind: MeteoErrorKind::RateLimited,
+                ..
+            }
+        )
+    }
+
+    pub(crate) fn meteo(reason: String) -> Self {
+        Self::Meteo {
+            kind: MeteoErrorKind::classify(&reason),
+            reason,
+        }
+    }
+}
+
+/// What an Open-Meteo error `reason` is about.
+///
+/// Open-Meteo only returns a human readable message, so this is a best effort
+/// classification based on the wording of that message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MeteoErrorKind {
+    /// Latitude or longitude out of range.
+    InvalidCoordinates,
+    /// Unknown variable requested.
+    InvalidVariable,
+    /// Date outside of the range covered by the API.
+    InvalidDate,
+    RateLimited,
+    Other,
+}
+
+impl MeteoErrorKind {
+    pub fn classify(reason: &str) -> Self {
+        let reason = reason.to_lowercase();
+
+        if reason.contains("limit exceeded") || reason.contains("too many requests") {
+            Self::RateLimited
+        } else if reason.contains("latitude") || reason.contains("longitude") {
+            Self::InvalidCoordinates
+        } else if reason.contains("invalid string value") || reason.contains("variable") {
+            Self::InvalidVariable
+        } else if reason.contains("date") {
+            Self::InvalidDate
+        } else {
+            Self::Other
+        }
+    }
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -1,36 +1,177 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, time::Duration};
 
-use reqwest::Client;
+use fjordgard_common::RequestTimer;
+use reqwest::{Client, RequestBuilder, StatusCode};
 
-pub use error::Error;
 use error::Result;
+pub use error::{Error, MeteoErrorKind};
 use model::*;
+use response::MeteoResponse;
 use serde::{Serialize, de::DeserializeOwned};
 
 mod error;
+mod mock;
 pub mod model;
+pub mod provider;
+mod response;
+
+/// Parsing of raw API payloads, for regression tests against fixtures.
+#[cfg(feature = "testing")]
+pub mod testing {
+    use reqwest::StatusCode;
+
+    pub use crate::response::MeteoResponse;
+    use crate::{error::Result, model::Forecast};
+
+    /// Parse a successful `/forecast` response body, as the client would.
+    pub fn parse_forecast(json: &str) -> Result<Forecast> {
+        crate::parse_response(StatusCode::OK, json)
+    }
+}
 
 #[cfg(not(target_arch = "wasm32"))]
 const USER_AGENT: &str = concat!("fjordgard/", env!("CARGO_PKG_VERSION"));
 const GEOCODING_API_HOST: &str = "geocoding-api.open-meteo.com";
 const FORECASTING_API_HOST: &str = "api.open-meteo.com";
+const MARINE_API_HOST: &str = "marine-api.open-meteo.com";
+const AIR_QUALITY_API_HOST: &str = "air-quality-api.open-meteo.com";
+/// Open-Meteo has no reverse geocoding, so OpenStreetMap's is used for names.
+const REVERSE_GEOCODING_URL: &str = "https://nominatim.openstreetmap.org/reverse";
+/// How many places named like the coordinates to pick the closest from.
+const REVERSE_GEOCODING_CANDIDATES: usize = 10;
+
+/// The location in `locations` closest to the given coordinates.
+fn nearest_location(locations: Vec<Location>, latitude: f64, longitude: f64) -> Option<Location> {
+    locations.into_iter().min_by(|a, b| {
+        a.distance_to(latitude, longitude)
+            .total_cmp(&b.distance_to(latitude, longitude))
+    })
+}
+
+/// Let Open-Meteo pick the time zone of the location, so returned times are
+/// local rather than GMT.
+fn with_auto_timezone(opt: Option<ForecastOptions>) -> ForecastOptions {
+    let mut opt = opt.unwrap_or_default();
+    opt.timezone.get_or_insert_with(|| "auto".to_string());
+    opt
+}
+
+#[derive(Default)]
+pub struct MeteoClientBuilder {
+    api_key: Option<String>,
+    timeout: Option<Duration>,
+    #[cfg(not(target_arch = "wasm32"))]
+    connect_timeout: Option<Duration>,
+    client: Option<Client>,
+    mock: bool,
+}
+
+impl MeteoClientBuilder {
+    pub fn api_key(mut self, api_key: &str) -> Self {
+        self.api_key = Some(api_key.to_string());
+        self
+    }
+
+    /// Total time allowed for each request, failing with [`Error::Timeout`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Time allowed for establishing a connection, not supported on wasm.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Send requests through an existing client, sharing its connection
+    /// pool. The user agent is added to each request instead, and
+    /// `connect_timeout` is ignored.
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Answer every request with bundled fixtures instead of the network,
+    /// for working offline.
+    pub fn mock(mut self, mock: bool) -> Self {
+        self.mock = mock;
+        self
+    }
+
+    pub fn build(self) -> Result<MeteoClient> {
+        let client = match self.client {
+            Some(client) => client,
+            #[cfg(not(target_arch = "wasm32"))]
+            None => {
+                let mut builder = Client::builder();
+
+                if let Some(connect_timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
+                }
+
+                builder.build()?
+            }
+            #[cfg(target_arch = "wasm32")]
+            None => Client::new(),
+        };
+
+        Ok(MeteoClient {
+            api_key: self.api_key,
+            client,
+            timeout: self.timeout,
+            mock: self.mock,
+        })
+    }
+}
 
 pub struct MeteoClient {
     api_key: Option<String>,
     client: Client,
+    timeout: Option<Duration>,
+    mock: bool,
 }
 
 impl MeteoClient {
     pub fn new(api_key: Option<&str>) -> Result<Self> {
+        let mut builder = Self::builder();
+
+        if let Some(api_key) = api_key {
+            builder = builder.api_key(api_key);
+        }
+
+        builder.build()
+    }
+
+    /// Like [`Self::new`], sharing the connection pool of `client`.
+    pub fn from_client(client: Client, api_key: Option<&str>) -> Result<Self> {
+        let mut builder = Self::builder().client(client);
+
+        if let Some(api_key) = api_key {
+            builder = builder.api_key(api_key);
+        }
+
+        builder.build()
+    }
+
+    pub fn builder() -> MeteoClientBuilder {
+        MeteoClientBuilder::default()
+    }
+
+    fn get(&self, url: &str) -> RequestBuilder {
+        let req = self.client.get(url);
+
+        // browsers don't allow setting the user agent
         #[cfg(not(target_arch = "wasm32"))]
-        let client = Client::builder().user_agent(USER_AGENT).build()?;
-        #[cfg(target_arch = "wasm32")]
-        let client = Client::new();
+        let req = req.header(reqwest::header::USER_AGENT, USER_AGENT);
 
-        Ok(Self {
-            api_key: api_key.map(|k| k.to_string()),
-            client,
-        })
+        // applied per request rather than on the client as wasm only
+        // supports the former
+        match self.timeout {
+            Some(timeout) => req.timeout(timeout),
+            None => req,
+        }
     }
 
     async fn request<O1: Serialize, O2: Serialize, T: DeserializeOwned + Debug>(
@@ -40,13 +181,19 @@ impl MeteoClient {
         opt1: Option<O1>,
         opt2: Option<O2>,
     ) -> Result<T> {
+        if self.mock {
+            let (status, body) = mock::respond(route);
+
+            return parse_response(status, body);
+        }
+
         let prefix = if self.api_key.is_some() {
             "customer-"
         } else {
             ""
         };
 
-        let mut req = self.client.get(format!("https://{prefix}{url}/v1/{route}"));
+        let mut req = self.get(&format!("https://{prefix}{url}/v1/{route}"));
 
         if let Some(ref key) = self.api_key {
             req = req.query(&[("apikey", key)])
@@ -60,15 +207,29 @@ impl MeteoClient {
             req = req.query(opt)
         };
 
-        let resp: MeteoResponse = req.send().await?.json().await?;
+        let timer = RequestTimer::start(format!("{url}/v1/{route}"));
 
-        match resp {
-            MeteoResponse::Error { reason } => Err(Error::Meteo(reason)),
-            MeteoResponse::Success(v) => match serde_json::from_value(v) {
-                Ok(o) => Ok(o),
-                Err(e) => Err(Error::SerdeJson(e)),
-            },
-        }
+        let fetched = timer
+            .instrument(async {
+                let res = req.send().await?;
+                let status = res.status();
+                let body = res.text().await?;
+
+                Ok::<_, Error>((status, body))
+            })
+            .await;
+
+        let (status, body) = match fetched {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                timer.fail(&e);
+                return Err(e);
+            }
+        };
+
+        timer.finish(status.as_u16(), body.len());
+
+        parse_response(status, &body)
     }
 
     /// Endpoint: `/search`
@@ -80,7 +241,74 @@ impl MeteoClient {
         Ok(resp.results)
     }
 
+    /// Endpoint: Nominatim `/reverse`, then `/search`
+    ///
+    /// Resolves the coordinates to a place name, then returns the closest of
+    /// the Open-Meteo locations with that name. `None` when the coordinates
+    /// aren't near any named place.
+    pub async fn reverse_geocode(&self, latitude: f64, longitude: f64) -> Result<Option<Location>> {
+        let place: ReversePlace = if self.mock {
+            serde_json::from_str(mock::REVERSE_PLACE)?
+        } else {
+            self.reverse_place(latitude, longitude).await?
+        };
+
+        let name = match place.name {
+            Some(name) if !name.is_empty() => name,
+            _ => return Ok(None),
+        };
+
+        let locations = self
+            .geocode(
+                &name,
+                Some(GeocodeOptions {
+                    count: Some(REVERSE_GEOCODING_CANDIDATES),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+
+        Ok(nearest_location(locations, latitude, longitude))
+    }
+
+    async fn reverse_place(&self, latitude: f64, longitude: f64) -> Result<ReversePlace> {
+        let req = self.get(REVERSE_GEOCODING_URL).query(&[
+            ("lat", latitude.to_string()),
+            ("lon", longitude.to_string()),
+            ("format", "jsonv2".to_string()),
+            // city level
+            ("zoom", "10".to_string()),
+        ]);
+
+        let timer = RequestTimer::start(REVERSE_GEOCODING_URL);
+
+        let fetched = timer
+            .instrument(async {
+                let res = req.send().await?.error_for_status()?;
+                let status = res.status();
+                let body = res.bytes().await?;
+
+                Ok::<_, Error>((status, body))
+            })
+            .await;
+
+        let body = match fetched {
+            Ok((status, body)) => {
+                timer.finish(status.as_u16(), body.len());
+                body
+            }
+            Err(e) => {
+                timer.fail(&e);
+                return Err(e);
+            }
+        };
+
+        Ok(serde_json::from_slice(&body)?)
+    }
+
     /// Endpoint: `/forecast`
+    ///
+    /// `timezone` defaults to `auto`.
     pub async fn forecast_single(
         &self,
         latitude: f64,
@@ -91,14 +319,146 @@ impl MeteoClient {
             FORECASTING_API_HOST,
             "forecast",
             Some(&[("latitude", latitude), ("longitude", longitude)]),
+            Some(with_auto_timezone(opt)),
+        )
+        .await
+    }
+
+    /// Endpoint: `/forecast`, for several locations in one request
+    ///
+    /// `timezone` defaults to `auto`.
+    pub async fn forecast_many(
+        &self,
+        coords: &[(f64, f64)],
+        opt: Option<ForecastOptions>,
+    ) -> Result<Vec<Forecast>> {
+        if coords.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let join = |f: fn(&(f64, f64)) -> f64| {
+            coords
+                .iter()
+                .map(|c| f(c).to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+
+        let resp: OneOrMany<Forecast> = self
+            .request(
+                FORECASTING_API_HOST,
+                "forecast",
+                Some(&[("latitude", join(|c| c.0)), ("longitude", join(|c| c.1))]),
+                Some(with_auto_timezone(opt)),
+            )
+            .await?;
+
+        Ok(resp.into())
+    }
+
+    /// Endpoint: `/marine`
+    pub async fn marine_forecast(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        opt: Option<MarineOptions>,
+    ) -> Result<MarineForecast> {
+        self.request(
+            MARINE_API_HOST,
+            "marine",
+            Some(&[("latitude", latitude), ("longitude", longitude)]),
             opt,
         )
         .await
     }
+
+    /// Endpoint: `/air-quality`
+    pub async fn air_quality(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        opt: Option<AirQualityOptions>,
+    ) -> Result<AirQuality> {
+        self.request(
+            AIR_QUALITY_API_HOST,
+            "air-quality",
+            Some(&[("latitude", latitude), ("longitude", longitude)]),
+            opt,
+        )
+        .await
+    }
+
+    /// Endpoint: `/forecast` and `/air-quality`, requested concurrently
+    ///
+    /// Air quality isn't covered everywhere, so failing to fetch it leaves
+    /// [`Conditions::air_quality`] empty rather than failing the call.
+    pub async fn current_conditions(&self, latitude: f64, longitude: f64) -> Result<Conditions> {
+        let forecast_opt = ForecastOptions {
+            current: Some(vec![
+                CurrentVariable::Temperature2m,
+                CurrentVariable::ApparentTemperature,
+                CurrentVariable::RelativeHumidity2m,
+                CurrentVariable::WeatherCode,
+                CurrentVariable::IsDay,
+                CurrentVariable::WindSpeed10m,
+            ]),
+            ..Default::default()
+        };
+        let air_quality_opt = AirQualityOptions {
+            current: Some(vec![
+                AirQualityVariable::EuropeanAqi,
+                AirQualityVariable::UsAqi,
+            ]),
+            ..Default::default()
+        };
+
+        let (forecast, air_quality) = tokio::join!(
+            self.forecast_single(latitude, longitude, Some(forecast_opt)),
+            self.air_quality(latitude, longitude, Some(air_quality_opt)),
+        );
+
+        Ok(Conditions {
+            forecast: forecast?,
+            air_quality: air_quality.ok(),
+        })
+    }
+}
+
+fn parse_response<T: DeserializeOwned>(status: StatusCode, body: &str) -> Result<T> {
+    let resp = serde_json::from_str::<MeteoResponse>(body);
+
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        let reason = match resp {
+            Ok(MeteoResponse::Error { reason }) => reason,
+            _ => String::from("too many requests"),
+        };
+
+        return Err(Error::Meteo {
+            reason,
+            kind: MeteoErrorKind::RateLimited,
+        });
+    }
+
+    // only successes and bad requests are documented to have a JSON body
+    if !status.is_success() && status != StatusCode::BAD_REQUEST {
+        return Err(Error::Http {
+            status: status.as_u16(),
+        });
+    }
+
+    match resp? {
+        MeteoResponse::Error { reason } => Err(Error::meteo(reason)),
+        MeteoResponse::Success(v) => match serde_json::from_value(v) {
+            Ok(o) => Ok(o),
+            Err(e) => Err(Error::SerdeJson(e)),
+        },
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::{DateTime, NaiveDate, TimeDelta};
+
     use super::*;
 
     async fn get_london(client: &MeteoClient) -> Location {
@@ -118,6 +478,825 @@ mod tests {
         assert_eq!(london.timezone, "Europe/London");
         assert_eq!(london.admin1, Some("England".to_string()));
         assert_eq!(london.country, "United Kingdom");
+        assert_eq!(london.display_name(), "London, England, United Kingdom");
+        assert_eq!(london.short_name(), "London, GB");
+    }
+
+    #[test]
+    fn nearest_location() {
+        let location = |name: &str, latitude: f64, longitude: f64| -> Location {
+            serde_json::from_value(serde_json::json!({
+                "id": 0, "name": name, "latitude": latitude, "longitude": longitude,
+                "elevation": 0.0, "timezone": "UTC", "feature_code": "PPL",
+                "country_code": "US", "country": "United States", "country_id": 0
+            }))
+            .unwrap()
+        };
+
+        let locations = vec![
+            location("Springfield", 39.80, -89.64),
+            location("Springfield", 37.22, -93.30),
+            location("Springfield", 42.10, -72.59),
+        ];
+
+        // near Springfield, Missouri
+        let nearest = super::nearest_location(locations, 37.0, -93.0).unwrap();
+        assert_eq!(nearest.coordinates(), (37.22, -93.30));
+        assert!(nearest.distance_to(37.0, -93.0) < 50.0);
+
+        assert!(super::nearest_location(Vec::new(), 0.0, 0.0).is_none());
+    }
+
+    #[tokio::test]
+    async fn marine_forecast() {
+        let client = MeteoClient::new(None).unwrap();
+
+        // Bay of Biscay
+        let forecast = client
+            .marine_forecast(
+                45.0,
+                -3.0,
+                Some(MarineOptions {
+                    current: Some(vec![MarineVariable::WaveHeight]),
+                    hourly: Some(vec![
+                        MarineVariable::WaveHeight,
+                        MarineVariable::SwellWaveHeight,
+                    ]),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+
+        assert!(
+            forecast
+                .hourly
+                .unwrap()
+                .data
+                .contains_key(&MarineVariable::SwellWaveHeight)
+        );
+    }
+
+    #[tokio::test]
+    async fn air_quality() {
+        let client = MeteoClient::new(None).unwrap();
+        let london = get_london(&client).await;
+
+        let air_quality = client
+            .air_quality(
+                london.latitude,
+                london.longitude,
+                Some(AirQualityOptions {
+                    current: Some(vec![AirQualityVariable::EuropeanAqi]),
+                    hourly: Some(vec![AirQualityVariable::Pm2_5, AirQualityVariable::UsAqi]),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+
+        assert!(
+            air_quality
+                .current
+                .unwrap()
+                .data
+                .contains_key(&AirQualityVariable::EuropeanAqi)
+        );
+    }
+
+    #[tokio::test]
+    async fn forecast_many() {
+        let client = MeteoClient::new(None).unwrap();
+
+        let forecasts = client
+            .forecast_many(
+                &[(51.5, -0.12), (48.85, 2.35)],
+                Some(ForecastOptions {
+                    current: Some(vec![CurrentVariable::Temperature2m]),
+                    ..Default::default()
+                }),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(forecasts.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn current_conditions() {
+        let client = MeteoClient::new(None).unwrap();
+        let london = get_london(&client).await;
+
+        let conditions = client
+            .current_conditions(london.latitude, london.longitude)
+            .await
+            .unwrap();
+
+        assert!(
+            conditions
+                .forecast
+                .current
+                .unwrap()
+                .weather_code()
+                .is_some()
+        );
+        assert!(conditions.air_quality.is_some());
+    }
+
+    #[test]
+    fn one_or_many() {
+        let forecast = r#"{
+            "latitude": 51.5,
+            "longitude": -0.12,
+            "elevation": 23.0,
+            "utc_offset_seconds": 0,
+            "timezone": "GMT",
+            "timezone_abbreviation": "GMT"
+        }"#;
+
+        let one: OneOrMany<Forecast> = parse_response(StatusCode::OK, forecast).unwrap();
+        assert_eq!(Vec::from(one).len(), 1);
+
+        let many: OneOrMany<Forecast> =
+            parse_response(StatusCode::OK, &format!("[{forecast},{forecast}]")).unwrap();
+        assert_eq!(Vec::from(many).len(), 2);
+    }
+
+    #[test]
+    fn weather_code() {
+        assert_eq!(WeatherCode(0).description(true), "Sunny");
+        assert_eq!(WeatherCode(0).description(false), "Clear");
+        assert_eq!(WeatherCode(66).description(true), "Light freezing rain");
+        assert_eq!(WeatherCode(42).description(true), "Unknown");
+
+        assert_eq!(WeatherCode(48).icon_group(), 45);
+        assert_eq!(WeatherCode(86).icon_group(), 80);
+        assert_eq!(WeatherCode(99).icon_group(), 96);
+        assert_eq!(WeatherCode(42).icon_group(), 100);
+    }
+
+    #[test]
+    fn wind_direction() {
+        let point = |degrees| WindDirection(degrees).compass_point();
+
+        assert_eq!(point(0.0), "N");
+        assert_eq!(point(22.5), "NNE");
+        assert_eq!(point(100.0), "E");
+        assert_eq!(point(200.0), "SSW");
+        assert_eq!(point(355.0), "N");
+        assert_eq!(point(-90.0), "W");
+
+        // a northerly blows south
+        let rotation = WindDirection(0.0).arrow_rotation();
+        assert!((rotation - std::f32::consts::PI).abs() < 1e-6);
+        assert!(WindDirection(180.0).arrow_rotation().abs() < 1e-6);
+    }
+
+    #[test]
+    fn unit_conversion() {
+        let forecast: Forecast = serde_json::from_str(
+            r#"{
+                "latitude": 51.5,
+                "longitude": -0.12,
+                "elevation": 23.0,
+                "utc_offset_seconds": 0,
+                "timezone": "GMT",
+                "timezone_abbreviation": "GMT",
+                "current_units": {
+                    "time": "iso8601",
+                    "interval": "seconds",
+                    "temperature_2m": "°C",
+                    "wind_speed_10m": "km/h",
+                    "precipitation": "mm"
+                },
+                "current": {
+                    "time": "2025-06-01T12:00",
+                    "interval": 900,
+                    "temperature_2m": 20.0,
+                    "wind_speed_10m": 36.0,
+                    "precipitation": 25.4
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            forecast.current_temperature_in(TemperatureUnit::Fahrenheit),
+            Some(68.0)
+        );
+        assert_eq!(
+            forecast.current_temperature_in(TemperatureUnit::Celsius),
+            Some(20.0)
+        );
+
+        let speed = forecast
+            .current_wind_speed_in(SpeedUnit::MetersPerSecond)
+            .unwrap();
+        assert!((speed - 10.0).abs() < 1e-9);
+
+        let precipitation = forecast
+            .current_precipitation_in(PrecipitationUnit::Inch)
+            .unwrap();
+        assert!((precipitation - 1.0).abs() < 1e-9);
+
+        let without_units = Forecast {
+            current_units: None,
+            ..forecast
+        };
+        assert_eq!(without_units.current_wind_speed_in(SpeedUnit::Knots), None);
+    }
+
+    #[test]
+    fn minutely_15() {
+        let forecast: Forecast = serde_json::from_str(
+            r#"{
+                "latitude": 51.5,
+                "longitude": -0.12,
+                "elevation": 23.0,
+                "utc_offset_seconds": 0,
+                "timezone": "GMT",
+                "timezone_abbreviation": "GMT",
+                "minutely_15_units": {
+                    "time": "iso8601",
+                    "precipitation": "mm"
+                },
+                "minutely_15": {
+                    "time": ["2025-06-01T12:00", "2025-06-01T12:15"],
+                    "precipitation": [0.0, 0.3]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let minutely_15 = forecast.minutely_15.unwrap();
+
+        assert_eq!(minutely_15.time.len(), 2);
+        assert_eq!(
+            minutely_15.data.get(&Minutely15Variable::Precipitation),
+            Some(&vec![Some(0.0), Some(0.3)])
+        );
+        assert_eq!(
+            forecast
+                .minutely_15_units
+                .unwrap()
+                .get(&Minutely15Variable::Precipitation)
+                .map(String::as_str),
+            Some("mm")
+        );
+    }
+
+    #[test]
+    fn forecast_round_trip() {
+        let forecast: Forecast = serde_json::from_str(
+            r#"{
+                "latitude": 51.5,
+                "longitude": -0.12,
+                "elevation": 23.0,
+                "utc_offset_seconds": 0,
+                "timezone": "GMT",
+                "timezone_abbreviation": "GMT",
+                "hourly_units": {
+                    "time": "iso8601",
+                    "temperature_850hPa": "°C"
+                },
+                "hourly": {
+                    "time": ["2025-06-01T12:00"],
+                    "temperature_850hPa": [4.2]
+                },
+                "current_units": {
+                    "time": "iso8601",
+                    "interval": "seconds",
+                    "temperature_2m": "°C"
+                },
+                "current": {
+                    "time": "2025-06-01T12:00",
+                    "interval": 900,
+                    "temperature_2m": 18.5
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let forecast: Forecast =
+            serde_json::from_str(&serde_json::to_string(&forecast).unwrap()).unwrap();
+
+        assert_eq!(
+            forecast
+                .hourly
+                .unwrap()
+                .data
+                .get(&HourlyVariable::TemperaturePressureLevel(850)),
+            Some(&vec![Some(4.2)])
+        );
+        assert_eq!(
+            forecast
+                .current
+                .unwrap()
+                .data
+                .get(&CurrentVariable::Temperature2m),
+            Some(&18.5)
+        );
+    }
+
+    #[test]
+    fn null_values() {
+        let forecast: Forecast = serde_json::from_str(
+            r#"{
+                "latitude": 51.5,
+                "longitude": -0.12,
+                "elevation": 23.0,
+                "utc_offset_seconds": 0,
+                "timezone": "GMT",
+                "timezone_abbreviation": "GMT",
+                "hourly": {
+                    "time": ["2025-06-01T12:00", "2025-06-01T13:00"],
+                    "temperature_2m": [18.5, null],
+                    "snow_depth": [null, null]
+                },
+                "daily": {
+                    "time": ["2025-06-01"],
+                    "weather_code": [null]
+                },
+                "current": {
+                    "time": "2025-06-01T12:00",
+                    "interval": 900,
+                    "temperature_2m": 18.5,
+                    "weather_code": null
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let hourly = forecast.hourly.unwrap();
+        assert_eq!(
+            hourly.data.get(&HourlyVariable::Temperature2m),
+            Some(&vec![Some(18.5), None])
+        );
+        assert_eq!(
+            hourly.data.get(&HourlyVariable::SnowDepth),
+            Some(&vec![None, None])
+        );
+
+        assert_eq!(forecast.daily.unwrap().weather_codes(), Some(vec![None]));
+
+        let current = forecast.current.unwrap();
+        assert_eq!(current.weather_code(), None);
+        assert_eq!(
+            current.data.get(&CurrentVariable::Temperature2m),
+            Some(&18.5)
+        );
+    }
+
+    #[test]
+    fn marine_null_values() {
+        let marine: MarineForecast = serde_json::from_str(
+            r#"{
+                "latitude": 47.37,
+                "longitude": 8.54,
+                "utc_offset_seconds": 0,
+                "timezone": "GMT",
+                "timezone_abbreviation": "GMT",
+                "hourly": {
+                    "time": ["2025-06-01T12:00", "2025-06-01T13:00"],
+                    "wave_height": [null, null],
+                    "swell_wave_height": [0.4, null]
+                },
+                "current": {
+                    "time": "2025-06-01T12:00",
+                    "interval": 900,
+                    "wave_height": null
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let hourly = marine.hourly.unwrap();
+        assert_eq!(
+            hourly.data.get(&MarineVariable::WaveHeight),
+            Some(&vec![None, None])
+        );
+        assert_eq!(
+            hourly.data.get(&MarineVariable::SwellWaveHeight),
+            Some(&vec![Some(0.4), None])
+        );
+
+        assert!(marine.current.unwrap().data.is_empty());
+    }
+
+    #[test]
+    fn air_quality_null_values() {
+        let air_quality: AirQuality = serde_json::from_str(
+            r#"{
+                "latitude": 40.71,
+                "longitude": -74.01,
+                "utc_offset_seconds": 0,
+                "timezone": "GMT",
+                "timezone_abbreviation": "GMT",
+                "hourly": {
+                    "time": ["2025-06-01T12:00", "2025-06-01T13:00"],
+                    "pm10": [12.1, 13.4],
+                    "birch_pollen": [null, null]
+                },
+                "current": {
+                    "time": "2025-06-01T12:00",
+                    "interval": 3600,
+                    "us_aqi": 42.0,
+                    "grass_pollen": null
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let hourly = air_quality.hourly.unwrap();
+        assert_eq!(
+            hourly.data.get(&AirQualityVariable::BirchPollen),
+            Some(&vec![None, None])
+        );
+        assert_eq!(
+            hourly.data.get(&AirQualityVariable::Pm10),
+            Some(&vec![Some(12.1), Some(13.4)])
+        );
+
+        let current = air_quality.current.unwrap();
+        assert_eq!(current.data.get(&AirQualityVariable::UsAqi), Some(&42.0));
+        assert!(!current.data.contains_key(&AirQualityVariable::GrassPollen));
+    }
+
+    #[test]
+    fn hourly_series() {
+        let forecast: Forecast = serde_json::from_str(
+            r#"{
+                "latitude": 60.39,
+                "longitude": 5.32,
+                "elevation": 12.0,
+                "utc_offset_seconds": 7200,
+                "timezone": "Europe/Oslo",
+                "timezone_abbreviation": "GMT+2",
+                "hourly": {
+                    "time": ["2025-06-01T12:00", "2025-06-01T13:00", "2025-06-01T14:00"],
+                    "temperature_2m": [14.2, null, 15.1, 15.0]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let series = forecast
+            .hourly_series(&HourlyVariable::Temperature2m)
+            .unwrap();
+        let times: Vec<String> = series.iter().map(|(t, _)| t.to_rfc3339()).collect();
+
+        assert_eq!(
+            times,
+            ["2025-06-01T12:00:00+02:00", "2025-06-01T14:00:00+02:00"]
+        );
+        assert_eq!(series[1].1, 15.1);
+        assert!(forecast.hourly_series(&HourlyVariable::SnowDepth).is_none());
+    }
+
+    #[test]
+    fn next_precipitation() {
+        let hourly: HourlyData = serde_json::from_str(
+            r#"{
+                "time": [
+                    "2025-06-01T12:00",
+                    "2025-06-01T13:00",
+                    "2025-06-01T14:00",
+                    "2025-06-01T15:00",
+                    "2025-06-01T16:00"
+                ],
+                "precipitation_probability": [80, 20, 70, 90, 100],
+                "precipitation": [0.4, 0.0, 0.0, 1.2, 3.0]
+            }"#,
+        )
+        .unwrap();
+
+        let next = |now, within| {
+            hourly
+                .next_precipitation(
+                    DateTime::parse_from_rfc3339(now).unwrap(),
+                    TimeDelta::hours(within),
+                    50.0,
+                )
+                .map(|t| t.to_rfc3339())
+        };
+
+        // the current hour counts, the 14:00 hour expects nothing
+        assert_eq!(
+            next("2025-06-01T12:30:00+02:00", 6).as_deref(),
+            Some("2025-06-01T12:00:00+02:00")
+        );
+        assert_eq!(
+            next("2025-06-01T13:10:00+02:00", 6).as_deref(),
+            Some("2025-06-01T15:00:00+02:00")
+        );
+        assert_eq!(next("2025-06-01T13:10:00+02:00", 1), None);
+    }
+
+    #[test]
+    fn sun_times() {
+        let forecast: Forecast = serde_json::from_str(
+            r#"{
+                "latitude": 60.39,
+                "longitude": 5.32,
+                "elevation": 12.0,
+                "utc_offset_seconds": 7200,
+                "timezone": "Europe/Oslo",
+                "timezone_abbreviation": "GMT+2",
+                "daily_units": {
+                    "time": "iso8601",
+                    "sunrise": "iso8601",
+                    "sunset": "iso8601",
+                    "temperature_2m_max": "°C"
+                },
+                "daily": {
+                    "time": ["2025-06-01", "2025-06-02"],
+                    "sunrise": ["2025-06-01T04:12", null],
+                    "sunset": ["2025-06-01T22:31", "2025-06-02T22:33"],
+                    "temperature_2m_max": [15.1, 13.4]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let june = |day| NaiveDate::from_ymd_opt(2025, 6, day).unwrap();
+
+        assert_eq!(
+            forecast.sunrise_on(june(1)).unwrap().to_rfc3339(),
+            "2025-06-01T04:12:00+02:00"
+        );
+        assert_eq!(
+            forecast.sunset_on(june(2)).unwrap().to_rfc3339(),
+            "2025-06-02T22:33:00+02:00"
+        );
+        assert!(forecast.sunrise_on(june(2)).is_none());
+        assert!(forecast.sunset_on(june(3)).is_none());
+
+        let daily = forecast.daily.unwrap();
+        assert_eq!(
+            daily.data.get(&DailyVariable::Temperature2mMax),
+            Some(&vec![Some(15.1), Some(13.4)])
+        );
+        assert!(!daily.data.contains_key(&DailyVariable::Sunrise));
+    }
+
+    #[test]
+    fn unknown_variables() {
+        let forecast: Forecast = serde_json::from_str(
+            r#"{
+                "latitude": 51.5,
+                "longitude": -0.12,
+                "elevation": 23.0,
+                "utc_offset_seconds": 0,
+                "timezone": "GMT",
+                "timezone_abbreviation": "GMT",
+                "hourly": {
+                    "time": ["2025-06-01T12:00"],
+                    "brand_new_variable": [1.0],
+                    "vertical_velocity_850hPa": [0.2]
+                },
+                "current": {
+                    "time": "2025-06-01T12:00",
+                    "interval": 900,
+                    "brand_new_variable": 2.0
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let hourly = forecast.hourly.unwrap();
+        let current = forecast.current.unwrap();
+        let raw = HourlyVariable::Raw("brand_new_variable".to_string());
+
+        assert_eq!(hourly.data.get(&raw), Some(&vec![Some(1.0)]));
+        assert_eq!(
+            hourly
+                .data
+                .get(&HourlyVariable::Raw("vertical_velocity_850hPa".to_string())),
+            Some(&vec![Some(0.2)])
+        );
+        assert_eq!(
+            current
+                .data
+                .get(&CurrentVariable::Raw("brand_new_variable".to_string())),
+            Some(&2.0)
+        );
+        assert_eq!(
+            serde_json::to_string(&raw).unwrap(),
+            r#""brand_new_variable""#
+        );
+    }
+
+    /// Whether `opt` serializes to a query string containing `pair`.
+    fn query_contains(opt: &ForecastOptions, pair: &str) -> bool {
+        let req = Client::new()
+            .get("https://example.com")
+            .query(opt)
+            .build()
+            .unwrap();
+
+        req.url()
+            .query()
+            .unwrap_or_default()
+            .split('&')
+            .any(|p| p == pair)
+    }
+
+    #[test]
+    fn forecast_options_builder() {
+        let opt = ForecastOptions::builder()
+            .current(vec![
+                CurrentVariable::Temperature2m,
+                CurrentVariable::WeatherCode,
+            ])
+            .hourly(vec![HourlyVariable::TemperaturePressureLevel(850)])
+            .timezone("auto")
+            .forecast_days(3)
+            .build()
+            .unwrap();
+
+        assert!(query_contains(
+            &opt,
+            "current=temperature_2m%2Cweather_code"
+        ));
+        assert!(query_contains(&opt, "hourly=temperature_850hPa"));
+        assert!(query_contains(&opt, "timezone=auto"));
+        assert!(query_contains(&opt, "forecast_days=3"));
+        assert!(!query_contains(&opt, "daily="));
+    }
+
+    #[test]
+    fn forecast_options_builder_validates() {
+        let half_range = ForecastOptions::builder().start_date("2025-06-01").build();
+        assert!(matches!(half_range, Err(Error::InvalidOptions(_))));
+
+        let conflicting = ForecastOptions::builder()
+            .start_date("2025-06-01")
+            .end_date("2025-06-07")
+            .forecast_days(3)
+            .build();
+        assert!(matches!(conflicting, Err(Error::InvalidOptions(_))));
+
+        ForecastOptions::builder()
+            .start_date("2025-06-01")
+            .end_date("2025-06-07")
+            .build()
+            .unwrap();
+    }
+
+    #[test]
+    fn local_time_of() {
+        let forecast: Forecast = serde_json::from_str(
+            r#"{
+                "latitude": 59.9,
+                "longitude": 10.75,
+                "elevation": 23.0,
+                "utc_offset_seconds": 7200,
+                "timezone": "Europe/Oslo",
+                "timezone_abbreviation": "GMT+2",
+                "hourly": {
+                    "time": ["2025-06-01T00:00", "2025-06-01T01:00"],
+                    "temperature_2m": [12.0, 11.5]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let time = forecast.local_time_of(1).unwrap();
+
+        assert_eq!(time.to_rfc3339(), "2025-06-01T01:00:00+02:00");
+        assert!(forecast.local_time_of(2).is_none());
+        assert!(forecast.current_local_time().is_none());
+    }
+
+    #[test]
+    fn weather_models() {
+        let opt = ForecastOptions::builder()
+            .models(vec![
+                WeatherModel::EcmwfIfs025,
+                WeatherModel::MetnoSeamless,
+                WeatherModel::Custom("icon_d2".to_string()),
+            ])
+            .build()
+            .unwrap();
+
+        assert!(query_contains(
+            &opt,
+            "models=ecmwf_ifs025%2Cmetno_seamless%2Cicon_d2"
+        ));
+        assert_eq!(
+            "gfs_seamless".parse::<WeatherModel>().unwrap(),
+            WeatherModel::GfsSeamless
+        );
+        assert_eq!(
+            "icon_d2".parse::<WeatherModel>().unwrap(),
+            WeatherModel::Custom("icon_d2".to_string())
+        );
+    }
+
+    #[test]
+    fn rate_limited() {
+        let body = r#"{"error":true,"reason":"Minutely API request limit exceeded. Please try again in one minute."}"#;
+
+        let err = parse_response::<Forecast>(StatusCode::TOO_MANY_REQUESTS, body).unwrap_err();
+        assert!(err.is_rate_limited());
+
+        let err = parse_response::<Forecast>(StatusCode::BAD_REQUEST, body).unwrap_err();
+        assert!(err.is_rate_limited());
+
+        let err = parse_response::<Forecast>(StatusCode::TOO_MANY_REQUESTS, "").unwrap_err();
+        assert!(err.is_rate_limited());
+    }
+
+    #[test]
+    fn meteo_error() {
+        let body = r#"{"error":true,"reason":"Cannot initialize WeatherVariable from invalid String value tempeture_2m for key hourly"}"#;
+
+        let err = parse_response::<Forecast>(StatusCode::BAD_REQUEST, body).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Meteo {
+                kind: MeteoErrorKind::InvalidVariable,
+                ..
+            }
+        ));
+
+        let body =
+            r#"{"error":true,"reason":"Latitude must be in range of -90 to 90°. Given: 91.0."}"#;
+
+        let err = parse_response::<Forecast>(StatusCode::BAD_REQUEST, body).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Meteo {
+                kind: MeteoErrorKind::InvalidCoordinates,
+                ..
+            }
+        ));
+
+        let body = r#"{"error":true,"reason":"Parameter 'start_date' is out of allowed range from 2016-01-01 to 2025-07-01"}"#;
+
+        let err = parse_response::<Forecast>(StatusCode::BAD_REQUEST, body).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Meteo {
+                kind: MeteoErrorKind::InvalidDate,
+                ..
+            }
+        ));
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn parse_forecast() {
+        use testing::{MeteoResponse, parse_forecast};
+
+        let forecast = parse_forecast(include_str!("../fixtures/forecast.json")).unwrap();
+        assert_eq!(forecast.timezone, "Europe/Oslo");
+
+        let body = r#"{"error":true,"reason":"Invalid timezone"}"#;
+        assert!(matches!(
+            serde_json::from_str(body),
+            Ok(MeteoResponse::Error { .. })
+        ));
+        assert!(parse_forecast(body).is_err());
+    }
+
+    #[test]
+    fn http_error() {
+        let body = "<html><body><h1>502 Bad Gateway</h1></body></html>";
+
+        let err = parse_response::<Forecast>(StatusCode::BAD_GATEWAY, body).unwrap_err();
+        assert!(matches!(err, Error::Http { status: 502 }));
+        assert!(err.is_transient());
+        assert_eq!(err.to_string(), "weather service unavailable (502)");
+
+        let err = parse_response::<Forecast>(StatusCode::NOT_FOUND, "").unwrap_err();
+        assert!(matches!(err, Error::Http { status: 404 }));
+        assert!(!err.is_transient());
+    }
+
+    #[tokio::test]
+    async fn mock() {
+        let client = MeteoClient::builder().mock(true).build().unwrap();
+
+        let bergen = client.reverse_geocode(60.4, 5.3).await.unwrap().unwrap();
+        assert_eq!(bergen.name, "Bergen");
+
+        let conditions = client
+            .current_conditions(bergen.latitude, bergen.longitude)
+            .await
+            .unwrap();
+        let current = conditions.forecast.current.unwrap();
+        assert_eq!(current.weather_code(), Some(WeatherCode(2)));
+        assert_eq!(current.is_day(), Some(true));
+        assert!(conditions.air_quality.is_some());
+
+        let daily = conditions.forecast.daily.unwrap();
+        assert_eq!(daily.weather_codes().unwrap().len(), 5);
+
+        client
+            .marine_forecast(bergen.latitude, bergen.longitude, None)
+            .await
+            .unwrap();
     }
 
     #[tokio::test]
@@ -136,6 +1315,8 @@ mod tests {
                         HourlyVariable::Temperature2m,
                         HourlyVariable::TemperaturePressureLevel(1000),
                     ]),
+                    minutely_15: Some(vec![Minutely15Variable::Precipitation]),
+                    forecast_minutely_15: Some(4),
                     ..Default::default()
                 }),
             )
@@ -0,0 +1,23 @@
+//! Canned responses served by clients built with
+//! [`MeteoClientBuilder::mock`](crate::MeteoClientBuilder::mock), so the app
+//! can run without network. Every location gets the same weather.
+
+use reqwest::StatusCode;
+
+const GEOCODE: &str = include_str!("../fixtures/geocode.json");
+const FORECAST: &str = include_str!("../fixtures/forecast.json");
+const MARINE: &str = include_str!("../fixtures/marine.json");
+const AIR_QUALITY: &str = include_str!("../fixtures/air_quality.json");
+/// Nominatim `/reverse` response.
+pub(crate) const REVERSE_PLACE: &str = include_str!("../fixtures/reverse.json");
+
+/// The status and body the API would answer `route` with.
+pub(crate) fn respond(route: &str) -> (StatusCode, &'static str) {
+    match route {
+        "search" => (StatusCode::OK, GEOCODE),
+        "forecast" => (StatusCode::OK, FORECAST),
+        "marine" => (StatusCode::OK, MARINE),
+        "air-quality" => (StatusCode::OK, AIR_QUALITY),
+        _ => (StatusCode::NOT_FOUND, "Not Found"),
+    }
+}
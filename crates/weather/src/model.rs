@@ -1,16 +1,28 @@
-use std::{collections::HashMap, fmt::Display, hash::Hash, str::FromStr};
+use std::{collections::HashMap, hash::Hash, str::FromStr};
 
-use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Visitor};
-use serde_with::DeserializeFromStr;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeDelta, TimeZone, Utc};
+use serde::{Deserialize, Deserializer, Serialize, de::Visitor};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 use strum::{Display, EnumString};
 
 use crate::Error;
 
+/// Open-Meteo returns a bare object rather than an array when only a single
+/// location was requested.
 #[derive(Deserialize, Debug)]
 #[serde(untagged)]
-pub(crate) enum MeteoResponse {
-    Success(serde_json::Value),
-    Error { reason: String },
+pub(crate) enum OneOrMany<T> {
+    Many(Vec<T>),
+    One(T),
+}
+
+impl<T> From<OneOrMany<T>> for Vec<T> {
+    fn from(value: OneOrMany<T>) -> Self {
+        match value {
+            OneOrMany::Many(v) => v,
+            OneOrMany::One(v) => vec![v],
+        }
+    }
 }
 
 #[serde_with::skip_serializing_none]
@@ -56,14 +68,55 @@ pub struct Location {
     pub admin4_id: Option<usize>,
 }
 
+impl Location {
+    /// `name, admin1, country`, skipping `admin1` when absent.
+    pub fn display_name(&self) -> String {
+        match &self.admin1 {
+            Some(admin1) => format!("{}, {admin1}, {}", self.name, self.country),
+            None => format!("{}, {}", self.name, self.country),
+        }
+    }
+
+    /// `name, country_code`.
+    pub fn short_name(&self) -> String {
+        format!("{}, {}", self.name, self.country_code)
+    }
+
+    pub fn coordinates(&self) -> (f64, f64) {
+        (self.latitude, self.longitude)
+    }
+
+    /// Great-circle distance in kilometers to the given coordinates.
+    pub fn distance_to(&self, latitude: f64, longitude: f64) -> f64 {
+        const EARTH_RADIUS: f64 = 6371.0;
+
+        let (lat1, lat2) = (self.latitude.to_radians(), latitude.to_radians());
+        let d_lat = lat2 - lat1;
+        let d_lon = (longitude - self.longitude).to_radians();
+
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+
+        2.0 * EARTH_RADIUS * a.sqrt().asin()
+    }
+}
+
+/// The part of a Nominatim `/reverse` response we use, `name` is missing for
+/// coordinates that don't resolve to a place.
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct ReversePlace {
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub(crate) struct GeocodeResponse {
     #[serde(default)]
     pub(crate) results: Vec<Location>,
 }
 
-#[derive(Display, EnumString, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[derive(Display, EnumString, Clone, Debug, Hash, PartialEq, Eq, SerializeDisplay)]
 #[strum(serialize_all = "snake_case")]
+#[non_exhaustive]
 pub enum HourlyVariable {
     #[strum(to_string = "temperature_2m")]
     Temperature2m,
@@ -149,6 +202,9 @@ pub enum HourlyVariable {
     GeopotentialHeightPressureLevel(usize),
     /// NOTE: Not a valid variable, only found within `.hourly_units`
     Time,
+    /// A variable not listed above, kept as sent.
+    #[strum(default)]
+    Raw(String),
 }
 
 impl<'de> Deserialize<'de> for HourlyVariable {
@@ -167,8 +223,8 @@ impl<'de> Deserialize<'de> for HourlyVariable {
                 E: serde::de::Error,
             {
                 match Self::Value::from_str(v) {
-                    Ok(v) => Ok(v),
-                    Err(e) => {
+                    // pressure levels don't parse, so end up as raw too
+                    Ok(HourlyVariable::Raw(_)) | Err(_) => {
                         // temperature_{0}hPa
                         // relative_humidity_{0}hPa
                         // dew_point_{0}hPa
@@ -177,20 +233,20 @@ impl<'de> Deserialize<'de> for HourlyVariable {
                         // wind_direction_{0}hPa
                         // geopotential_height_{0}hPa
 
-                        if !v.ends_with("hPa") {
-                            return Err(serde::de::Error::custom(e));
-                        }
+                        let raw = || HourlyVariable::Raw(v.to_string());
 
-                        let stripped = v.strip_suffix("hPa").unwrap();
+                        let Some(stripped) = v.strip_suffix("hPa") else {
+                            return Ok(raw());
+                        };
 
-                        let pos = stripped
-                            .find(|c: char| c.is_ascii_digit())
-                            .ok_or(serde::de::Error::custom(Error::InvalidPressureLevel))?;
+                        let Some(pos) = stripped.find(|c: char| c.is_ascii_digit()) else {
+                            return Ok(raw());
+                        };
 
                         let var = &stripped[..pos];
-                        let num = stripped[pos..]
-                            .parse::<usize>()
-                            .map_err(serde::de::Error::custom)?;
+                        let Ok(num) = stripped[pos..].parse::<usize>() else {
+                            return Ok(raw());
+                        };
 
                         let res = match var {
                             "temperature_" => HourlyVariable::TemperaturePressureLevel(num),
@@ -204,11 +260,12 @@ impl<'de> Deserialize<'de> for HourlyVariable {
                             "geopotential_height_" => {
                                 HourlyVariable::GeopotentialHeightPressureLevel(num)
                             }
-                            _ => return Err(serde::de::Error::custom(Error::InvalidPressureLevel)),
+                            _ => raw(),
                         };
 
                         Ok(res)
                     }
+                    Ok(v) => Ok(v),
                 }
             }
         }
@@ -217,7 +274,74 @@ impl<'de> Deserialize<'de> for HourlyVariable {
     }
 }
 
-#[derive(Display, EnumString, Clone, Copy, Debug, Hash, PartialEq, Eq, DeserializeFromStr)]
+/// Variables available at 15-minutely resolution.
+///
+/// Unlike [`HourlyVariable`], there are no pressure level variants.
+#[derive(
+    Display,
+    EnumString,
+    Clone,
+    Copy,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    DeserializeFromStr,
+    SerializeDisplay,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum Minutely15Variable {
+    #[strum(to_string = "temperature_2m")]
+    Temperature2m,
+    #[strum(to_string = "relative_humidity_2m")]
+    RelativeHumidity2m,
+    #[strum(to_string = "dew_point_2m")]
+    DewPoint2m,
+    ApparentTemperature,
+    ShortwaveRadiation,
+    DirectRadiation,
+    DirectNormalIrradiance,
+    GlobalTiltedIrradiance,
+    GlobalTiltedIrradianceInstant,
+    DiffuseRadiation,
+    SunshineDuration,
+    LightningPotential,
+    Precipitation,
+    Snowfall,
+    Rain,
+    Showers,
+    SnowfallHeight,
+    FreezingLevelHeight,
+    Cape,
+    #[strum(to_string = "wind_speed_10m")]
+    WindSpeed10m,
+    #[strum(to_string = "wind_speed_80m")]
+    WindSpeed80m,
+    #[strum(to_string = "wind_direction_10m")]
+    WindDirection10m,
+    #[strum(to_string = "wind_direction_80m")]
+    WindDirection80m,
+    #[strum(to_string = "wind_gusts_10m")]
+    WindGusts10m,
+    Visibility,
+    WeatherCode,
+    IsDay,
+    /// NOTE: Not a valid variable, only found within `.minutely_15_units`
+    Time,
+}
+
+#[derive(
+    Display,
+    EnumString,
+    Clone,
+    Copy,
+    Debug,
+    Hash,
+    PartialEq,
+    Eq,
+    DeserializeFromStr,
+    SerializeDisplay,
+)]
 #[strum(serialize_all = "snake_case")]
 pub enum DailyVariable {
     #[strum(to_string = "temperature_2m_max")]
@@ -256,8 +380,11 @@ pub enum DailyVariable {
     Time,
 }
 
-#[derive(Display, EnumString, Clone, Copy, Debug, Hash, PartialEq, Eq, DeserializeFromStr)]
+#[derive(
+    Display, EnumString, Clone, Debug, Hash, PartialEq, Eq, DeserializeFromStr, SerializeDisplay,
+)]
 #[strum(serialize_all = "snake_case")]
+#[non_exhaustive]
 pub enum CurrentVariable {
     #[strum(to_string = "temperature_2m")]
     Temperature2m,
@@ -337,43 +464,127 @@ pub enum CurrentVariable {
     #[strum(to_string = "soil_moisture_27_to_81cm")]
     SoilMoisture28To81cm,
     IsDay,
+    /// A variable not listed above, kept as sent.
+    #[strum(default)]
+    Raw(String),
 }
 
-#[derive(Serialize)]
+#[derive(
+    Serialize, Deserialize, Display, strum::VariantArray, Clone, Copy, Debug, PartialEq, Default,
+)]
 #[serde(rename_all = "snake_case")]
 pub enum TemperatureUnit {
+    #[default]
+    #[strum(to_string = "Celsius (°C)")]
     Celsius,
+    #[strum(to_string = "Fahrenheit (°F)")]
     Fahrenheit,
 }
 
-#[derive(Serialize)]
+#[derive(
+    Serialize, Deserialize, Display, strum::VariantArray, Clone, Copy, Debug, PartialEq, Default,
+)]
 pub enum SpeedUnit {
+    #[default]
     #[serde(rename = "kmh")]
+    #[strum(to_string = "km/h")]
     KilometersPerHour,
     #[serde(rename = "ms")]
+    #[strum(to_string = "m/s")]
     MetersPerSecond,
     #[serde(rename = "mph")]
+    #[strum(to_string = "mph")]
     MilesPerHour,
     #[serde(rename = "kn")]
+    #[strum(to_string = "kn")]
     Knots,
 }
 
-#[derive(Serialize)]
+#[derive(
+    Serialize, Deserialize, Display, strum::VariantArray, Clone, Copy, Debug, PartialEq, Default,
+)]
 pub enum PrecipitationUnit {
+    #[default]
     #[serde(rename = "mm")]
+    #[strum(to_string = "Millimeters")]
     Millimeter,
     #[serde(rename = "inch")]
+    #[strum(to_string = "Inches")]
     Inch,
 }
 
-#[derive(Serialize)]
+impl TemperatureUnit {
+    /// Parse the unit symbol used in `*_units` maps.
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "°C" => Some(Self::Celsius),
+            "°F" => Some(Self::Fahrenheit),
+            _ => None,
+        }
+    }
+
+    pub fn convert(&self, value: f64, to: TemperatureUnit) -> f64 {
+        match (self, to) {
+            (Self::Celsius, Self::Fahrenheit) => value * 9.0 / 5.0 + 32.0,
+            (Self::Fahrenheit, Self::Celsius) => (value - 32.0) * 5.0 / 9.0,
+            _ => value,
+        }
+    }
+}
+
+impl SpeedUnit {
+    /// Parse the unit symbol used in `*_units` maps.
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "km/h" => Some(Self::KilometersPerHour),
+            "m/s" => Some(Self::MetersPerSecond),
+            "mp/h" | "mph" => Some(Self::MilesPerHour),
+            "kn" => Some(Self::Knots),
+            _ => None,
+        }
+    }
+
+    fn meters_per_second(&self) -> f64 {
+        match self {
+            Self::KilometersPerHour => 1.0 / 3.6,
+            Self::MetersPerSecond => 1.0,
+            Self::MilesPerHour => 0.44704,
+            Self::Knots => 1852.0 / 3600.0,
+        }
+    }
+
+    pub fn convert(&self, value: f64, to: SpeedUnit) -> f64 {
+        value * self.meters_per_second() / to.meters_per_second()
+    }
+}
+
+impl PrecipitationUnit {
+    /// Parse the unit symbol used in `*_units` maps.
+    pub fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "mm" => Some(Self::Millimeter),
+            "inch" => Some(Self::Inch),
+            _ => None,
+        }
+    }
+
+    pub fn convert(&self, value: f64, to: PrecipitationUnit) -> f64 {
+        match (self, to) {
+            (Self::Millimeter, Self::Inch) => value / 25.4,
+            (Self::Inch, Self::Millimeter) => value * 25.4,
+            _ => value,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
 #[serde(rename = "lowercase")]
 pub enum TimeFormat {
     Iso8601,
     UnixTime,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
 #[serde(rename = "lowercase")]
 pub enum CellSelection {
     Land,
@@ -381,16 +592,59 @@ pub enum CellSelection {
     Nearest,
 }
 
+/// Weather model used for a forecast, `models` in the API.
+#[derive(
+    Display, EnumString, Clone, Debug, Hash, PartialEq, Eq, DeserializeFromStr, SerializeDisplay,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum WeatherModel {
+    /// Picks the best models for the location, the default.
+    BestMatch,
+    #[strum(to_string = "ecmwf_ifs025")]
+    EcmwfIfs025,
+    GfsSeamless,
+    IconSeamless,
+    GemSeamless,
+    JmaSeamless,
+    MetnoSeamless,
+    MeteofranceSeamless,
+    UkmoSeamless,
+    KnmiSeamless,
+    DmiSeamless,
+    /// Any model not listed above, by its API name.
+    #[strum(default)]
+    Custom(String),
+}
+
+impl WeatherModel {
+    /// Every model except [`WeatherModel::Custom`].
+    pub const KNOWN: &[WeatherModel] = &[
+        Self::BestMatch,
+        Self::EcmwfIfs025,
+        Self::GfsSeamless,
+        Self::IconSeamless,
+        Self::GemSeamless,
+        Self::JmaSeamless,
+        Self::MetnoSeamless,
+        Self::MeteofranceSeamless,
+        Self::UkmoSeamless,
+        Self::KnmiSeamless,
+        Self::DmiSeamless,
+    ];
+}
+
 #[serde_with::skip_serializing_none]
-#[derive(Serialize, Default)]
+#[derive(Serialize, Default, Clone)]
 pub struct ForecastOptions {
     pub elevation: Option<f64>,
-    #[serde(serialize_with = "csv")]
+    #[serde(serialize_with = "fjordgard_common::csv")]
     pub hourly: Option<Vec<HourlyVariable>>,
-    #[serde(serialize_with = "csv")]
+    #[serde(serialize_with = "fjordgard_common::csv")]
     pub daily: Option<Vec<DailyVariable>>,
-    #[serde(serialize_with = "csv")]
+    #[serde(serialize_with = "fjordgard_common::csv")]
     pub current: Option<Vec<CurrentVariable>>,
+    #[serde(serialize_with = "fjordgard_common::csv")]
+    pub minutely_15: Option<Vec<Minutely15Variable>>,
     pub temperature_unit: Option<TemperatureUnit>,
     pub wind_speed_unit: Option<SpeedUnit>,
     pub precipitation_unit: Option<PrecipitationUnit>,
@@ -408,34 +662,417 @@ pub struct ForecastOptions {
     pub end_hour: Option<String>,
     pub start_minutely_15: Option<String>,
     pub end_minutely_15: Option<String>,
-    #[serde(serialize_with = "csv")]
-    pub models: Option<Vec<String>>,
+    #[serde(serialize_with = "fjordgard_common::csv")]
+    pub models: Option<Vec<WeatherModel>>,
     pub cell_selection: Option<CellSelection>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+impl ForecastOptions {
+    pub fn builder() -> ForecastOptionsBuilder {
+        ForecastOptionsBuilder::default()
+    }
+}
+
+/// Fluent alternative to filling in [`ForecastOptions`] by hand.
+#[derive(Default)]
+pub struct ForecastOptionsBuilder {
+    options: ForecastOptions,
+}
+
+impl ForecastOptionsBuilder {
+    pub fn elevation(mut self, elevation: f64) -> Self {
+        self.options.elevation = Some(elevation);
+        self
+    }
+
+    pub fn hourly(mut self, hourly: Vec<HourlyVariable>) -> Self {
+        self.options.hourly = Some(hourly);
+        self
+    }
+
+    pub fn daily(mut self, daily: Vec<DailyVariable>) -> Self {
+        self.options.daily = Some(daily);
+        self
+    }
+
+    pub fn current(mut self, current: Vec<CurrentVariable>) -> Self {
+        self.options.current = Some(current);
+        self
+    }
+
+    pub fn minutely_15(mut self, minutely_15: Vec<Minutely15Variable>) -> Self {
+        self.options.minutely_15 = Some(minutely_15);
+        self
+    }
+
+    pub fn temperature_unit(mut self, temperature_unit: TemperatureUnit) -> Self {
+        self.options.temperature_unit = Some(temperature_unit);
+        self
+    }
+
+    pub fn wind_speed_unit(mut self, wind_speed_unit: SpeedUnit) -> Self {
+        self.options.wind_speed_unit = Some(wind_speed_unit);
+        self
+    }
+
+    pub fn precipitation_unit(mut self, precipitation_unit: PrecipitationUnit) -> Self {
+        self.options.precipitation_unit = Some(precipitation_unit);
+        self
+    }
+
+    pub fn time_format(mut self, time_format: TimeFormat) -> Self {
+        self.options.time_format = Some(time_format);
+        self
+    }
+
+    pub fn timezone(mut self, timezone: &str) -> Self {
+        self.options.timezone = Some(timezone.to_string());
+        self
+    }
+
+    pub fn past_days(mut self, past_days: usize) -> Self {
+        self.options.past_days = Some(past_days);
+        self
+    }
+
+    pub fn past_hours(mut self, past_hours: usize) -> Self {
+        self.options.past_hours = Some(past_hours);
+        self
+    }
+
+    pub fn past_minutely_15(mut self, past_minutely_15: usize) -> Self {
+        self.options.past_minutely_15 = Some(past_minutely_15);
+        self
+    }
+
+    pub fn forecast_days(mut self, forecast_days: usize) -> Self {
+        self.options.forecast_days = Some(forecast_days);
+        self
+    }
+
+    pub fn forecast_hours(mut self, forecast_hours: usize) -> Self {
+        self.options.forecast_hours = Some(forecast_hours);
+        self
+    }
+
+    pub fn forecast_minutely_15(mut self, forecast_minutely_15: usize) -> Self {
+        self.options.forecast_minutely_15 = Some(forecast_minutely_15);
+        self
+    }
+
+    pub fn start_date(mut self, start_date: &str) -> Self {
+        self.options.start_date = Some(start_date.to_string());
+        self
+    }
+
+    pub fn end_date(mut self, end_date: &str) -> Self {
+        self.options.end_date = Some(end_date.to_string());
+        self
+    }
+
+    pub fn start_hour(mut self, start_hour: &str) -> Self {
+        self.options.start_hour = Some(start_hour.to_string());
+        self
+    }
+
+    pub fn end_hour(mut self, end_hour: &str) -> Self {
+        self.options.end_hour = Some(end_hour.to_string());
+        self
+    }
+
+    pub fn start_minutely_15(mut self, start_minutely_15: &str) -> Self {
+        self.options.start_minutely_15 = Some(start_minutely_15.to_string());
+        self
+    }
+
+    pub fn end_minutely_15(mut self, end_minutely_15: &str) -> Self {
+        self.options.end_minutely_15 = Some(end_minutely_15.to_string());
+        self
+    }
+
+    pub fn models(mut self, models: Vec<WeatherModel>) -> Self {
+        self.options.models = Some(models);
+        self
+    }
+
+    pub fn cell_selection(mut self, cell_selection: CellSelection) -> Self {
+        self.options.cell_selection = Some(cell_selection);
+        self
+    }
+
+    /// Fails with [`Error::InvalidOptions`] on combinations Open-Meteo would
+    /// reject.
+    pub fn build(self) -> Result<ForecastOptions, Error> {
+        let o = &self.options;
+
+        if o.start_date.is_some() != o.end_date.is_some() {
+            return Err(Error::InvalidOptions(
+                "start_date and end_date must be set together",
+            ));
+        }
+
+        if o.start_hour.is_some() != o.end_hour.is_some() {
+            return Err(Error::InvalidOptions(
+                "start_hour and end_hour must be set together",
+            ));
+        }
+
+        if o.start_minutely_15.is_some() != o.end_minutely_15.is_some() {
+            return Err(Error::InvalidOptions(
+                "start_minutely_15 and end_minutely_15 must be set together",
+            ));
+        }
+
+        if o.start_date.is_some() && (o.past_days.is_some() || o.forecast_days.is_some()) {
+            return Err(Error::InvalidOptions(
+                "start_date cannot be combined with past_days or forecast_days",
+            ));
+        }
+
+        Ok(self.options)
+    }
+}
+
+/// Values are `None` where Open-Meteo has no data, such as past the end of a
+/// model's range.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct HourlyData {
     pub time: Vec<String>,
     #[serde(flatten)]
-    pub data: HashMap<HourlyVariable, Vec<f64>>,
+    pub data: HashMap<HourlyVariable, Vec<Option<f64>>>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+/// Values are `None` where Open-Meteo has no data, such as outside regions
+/// with 15-minutely models.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Minutely15Data {
+    pub time: Vec<String>,
+    #[serde(flatten)]
+    pub data: HashMap<Minutely15Variable, Vec<Option<f64>>>,
+}
+
+/// Values are `None` where Open-Meteo has no data.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DailyData {
     pub time: Vec<String>,
+    /// [`DailyVariable::Sunrise`] times, kept apart as they aren't numbers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sunrise: Option<Vec<Option<String>>>,
+    /// [`DailyVariable::Sunset`] times, kept apart as they aren't numbers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sunset: Option<Vec<Option<String>>>,
     #[serde(flatten)]
-    pub data: HashMap<DailyVariable, Vec<f64>>,
+    pub data: HashMap<DailyVariable, Vec<Option<f64>>>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CurrentData {
     pub time: String,
     pub interval: usize,
-    #[serde(flatten)]
+    /// Variables Open-Meteo has no data for are left out.
+    #[serde(flatten, deserialize_with = "without_nulls")]
     pub data: HashMap<CurrentVariable, f64>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+/// Drop `null` values, for maps where a missing value is as good as an absent
+/// key.
+fn without_nulls<'de, D, K>(deserializer: D) -> Result<HashMap<K, f64>, D::Error>
+where
+    D: Deserializer<'de>,
+    K: Deserialize<'de> + Eq + Hash,
+{
+    let values = HashMap::<K, Option<f64>>::deserialize(deserializer)?;
+
+    Ok(values
+        .into_iter()
+        .filter_map(|(k, v)| Some((k, v?)))
+        .collect())
+}
+
+impl CurrentData {
+    pub fn wind_direction(&self) -> Option<WindDirection> {
+        self.data
+            .get(&CurrentVariable::WindDirection10m)
+            .map(|d| WindDirection(*d))
+    }
+
+    pub fn weather_code(&self) -> Option<WeatherCode> {
+        self.data
+            .get(&CurrentVariable::WeatherCode)
+            .map(|c| WeatherCode(*c as u8))
+    }
+
+    pub fn is_day(&self) -> Option<bool> {
+        self.data.get(&CurrentVariable::IsDay).map(|d| *d != 0.0)
+    }
+}
+
+impl HourlyData {
+    /// Values of `var` paired with their times, in the time zone of `offset`
+    /// (see [`Forecast::hourly_series`]). Missing values and times that
+    /// don't parse are skipped, as are values past the end of `time`.
+    ///
+    /// `None` if `var` wasn't requested. Only works with the default
+    /// `iso8601` time format.
+    pub fn series(
+        &self,
+        var: &HourlyVariable,
+        offset: FixedOffset,
+    ) -> Option<Vec<(DateTime<FixedOffset>, f64)>> {
+        let values = self.data.get(var)?;
+
+        Some(
+            self.time
+                .iter()
+                .zip(values)
+                .filter_map(|(time, value)| Some((local_time(time, offset)?, (*value)?)))
+                .collect(),
+        )
+    }
+
+    /// Start of the first hour between `now` and `within` later that's at
+    /// least `threshold` percent likely to see precipitation, including the
+    /// hour `now` falls in. Hours expecting no [`HourlyVariable::Precipitation`]
+    /// are skipped when it was requested.
+    ///
+    /// `now` must be in the location's time zone, see
+    /// [`Forecast::next_precipitation`]. `None` if
+    /// [`HourlyVariable::PrecipitationProbability`] wasn't requested or no
+    /// hour qualifies.
+    pub fn next_precipitation(
+        &self,
+        now: DateTime<FixedOffset>,
+        within: TimeDelta,
+        threshold: f64,
+    ) -> Option<DateTime<FixedOffset>> {
+        let offset = *now.offset();
+        let probabilities = self.series(&HourlyVariable::PrecipitationProbability, offset)?;
+        let amounts: HashMap<_, _> = self
+            .series(&HourlyVariable::Precipitation, offset)
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+
+        probabilities
+            .into_iter()
+            .find(|(time, probability)| {
+                *time + TimeDelta::hours(1) > now
+                    && *time <= now + within
+                    && *probability >= threshold
+                    && amounts.get(time).is_none_or(|amount| *amount > 0.0)
+            })
+            .map(|(time, _)| time)
+    }
+}
+
+impl DailyData {
+    /// `None` for days without a code.
+    pub fn weather_codes(&self) -> Option<Vec<Option<WeatherCode>>> {
+        self.data.get(&DailyVariable::WeatherCode).map(|codes| {
+            codes
+                .iter()
+                .map(|c| c.map(|c| WeatherCode(c as u8)))
+                .collect()
+        })
+    }
+}
+
+/// WMO weather interpretation code, as returned for `weather_code`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WeatherCode(pub u8);
+
+impl WeatherCode {
+    pub fn description(&self, is_day: bool) -> &'static str {
+        match self.0 {
+            0 => {
+                if is_day {
+                    "Sunny"
+                } else {
+                    "Clear"
+                }
+            }
+            1 => {
+                if is_day {
+                    "Mainly sunny"
+                } else {
+                    "Mainly clear"
+                }
+            }
+            2 => "Partly cloudy",
+            3 => "Overcast",
+            45 => "Foggy",
+            48 => "Rime fog",
+            51 => "Light drizzle",
+            53 => "Drizzle",
+            55 => "Heavy drizzle",
+            56 => "Light freezing drizzle",
+            57 => "Freezing drizzle",
+            61 => "Light rain",
+            63 => "Rain",
+            65 => "Heavy rain",
+            66 => "Light freezing rain",
+            67 => "Freezing rain",
+            71 => "Light snow",
+            73 => "Snow",
+            75 => "Heavy snow",
+            77 => "Snow grains",
+            80 => "Light showers",
+            81 => "Showers",
+            82 => "Heavy showers",
+            85 => "Light snow showers",
+            86 => "Snow showers",
+            95 => "Thunderstorm",
+            96 => "Light thunderstorm with hail",
+            99 => "Thunderstorm with hail",
+            _ => "Unknown",
+        }
+    }
+
+    /// Representative code shared by similar conditions, `100` if unknown.
+    pub fn icon_group(&self) -> u8 {
+        match self.0 {
+            0 => 0,
+            1 => 1,
+            2 => 2,
+            3 => 3,
+            45 | 48 => 45,
+            51 | 53 | 55 | 56 | 57 => 51,
+            61 | 63 | 65 | 66 | 67 => 61,
+            71 | 73 | 75 => 71,
+            77 => 77,
+            80 | 81 | 82 | 85 | 86 => 80,
+            95 => 95,
+            96 | 99 => 96,
+            _ => 100,
+        }
+    }
+}
+
+/// Direction the wind blows from in degrees clockwise from north, as returned
+/// for `wind_direction_*`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindDirection(pub f64);
+
+impl WindDirection {
+    /// Label on a 16-point compass, such as `NNE`.
+    pub fn compass_point(&self) -> &'static str {
+        const POINTS: [&str; 16] = [
+            "N", "NNE", "NE", "ENE", "E", "ESE", "SE", "SSE", "S", "SSW", "SW", "WSW", "W", "WNW",
+            "NW", "NNW",
+        ];
+
+        let index = (self.0.rem_euclid(360.0) / 22.5).round() as usize % POINTS.len();
+        POINTS[index]
+    }
+
+    /// Clockwise rotation in radians turning an arrow pointing up into one
+    /// pointing where the wind blows to.
+    pub fn arrow_rotation(&self) -> f32 {
+        (self.0 + 180.0).rem_euclid(360.0).to_radians() as f32
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Forecast {
     pub latitude: f64,
     pub longitude: f64,
@@ -445,22 +1082,330 @@ pub struct Forecast {
     pub timezone_abbreviation: String,
     pub hourly: Option<HourlyData>,
     pub hourly_units: Option<HashMap<HourlyVariable, String>>,
+    pub minutely_15: Option<Minutely15Data>,
+    pub minutely_15_units: Option<HashMap<Minutely15Variable, String>>,
     pub daily: Option<DailyData>,
     pub daily_units: Option<HashMap<DailyVariable, String>>,
     pub current: Option<CurrentData>,
     pub current_units: Option<HashMap<CurrentVariable, String>>,
 }
 
-fn csv<S: Serializer, T: Display>(list: &Option<Vec<T>>, serializer: S) -> Result<S::Ok, S::Error> {
-    if let Some(list) = list {
-        let s: String = list
-            .iter()
-            .map(|v| v.to_string())
-            .collect::<Vec<String>>()
-            .join(",");
+/// Parse an `iso8601` time returned by Open-Meteo, which is already in the
+/// location's zone, only the offset is missing.
+fn local_time(time: &str, offset: FixedOffset) -> Option<DateTime<FixedOffset>> {
+    let naive = NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M").ok()?;
+
+    offset.from_local_datetime(&naive).single()
+}
+
+impl Forecast {
+    /// Offset of the location's time zone.
+    pub fn utc_offset(&self) -> Option<FixedOffset> {
+        FixedOffset::east_opt(self.utc_offset_seconds.try_into().ok()?)
+    }
+
+    fn local_time(&self, time: &str) -> Option<DateTime<FixedOffset>> {
+        local_time(time, self.utc_offset()?)
+    }
+
+    /// Current value of `var` and the unit symbol it came in.
+    fn current_with_unit(&self, var: CurrentVariable) -> Option<(f64, &str)> {
+        let value = *self.current.as_ref()?.data.get(&var)?;
+        let unit = self.current_units.as_ref()?.get(&var)?;
+
+        Some((value, unit))
+    }
+
+    /// Current `temperature_2m` converted to `unit`.
+    pub fn current_temperature_in(&self, unit: TemperatureUnit) -> Option<f64> {
+        let (value, from) = self.current_with_unit(CurrentVariable::Temperature2m)?;
+
+        Some(TemperatureUnit::from_symbol(from)?.convert(value, unit))
+    }
+
+    /// Current `wind_speed_10m` converted to `unit`.
+    pub fn current_wind_speed_in(&self, unit: SpeedUnit) -> Option<f64> {
+        let (value, from) = self.current_with_unit(CurrentVariable::WindSpeed10m)?;
+
+        Some(SpeedUnit::from_symbol(from)?.convert(value, unit))
+    }
+
+    /// Current `precipitation` converted to `unit`.
+    pub fn current_precipitation_in(&self, unit: PrecipitationUnit) -> Option<f64> {
+        let (value, from) = self.current_with_unit(CurrentVariable::Precipitation)?;
+
+        Some(PrecipitationUnit::from_symbol(from)?.convert(value, unit))
+    }
+
+    /// Time of the `index`th hourly value, in the location's time zone.
+    ///
+    /// Only works with the default `iso8601` time format.
+    pub fn local_time_of(&self, index: usize) -> Option<DateTime<FixedOffset>> {
+        self.local_time(self.hourly.as_ref()?.time.get(index)?)
+    }
+
+    /// Hourly values of `var` paired with their times, in the location's time
+    /// zone. See [`HourlyData::series`].
+    pub fn hourly_series(&self, var: &HourlyVariable) -> Option<Vec<(DateTime<FixedOffset>, f64)>> {
+        self.hourly.as_ref()?.series(var, self.utc_offset()?)
+    }
+
+    /// Start of the next hour likely to see precipitation within `within`,
+    /// see [`HourlyData::next_precipitation`].
+    pub fn next_precipitation(
+        &self,
+        within: TimeDelta,
+        threshold: f64,
+    ) -> Option<DateTime<FixedOffset>> {
+        let now = Utc::now().with_timezone(&self.utc_offset()?);
+        self.hourly
+            .as_ref()?
+            .next_precipitation(now, within, threshold)
+    }
+
+    /// Sunrise on `date` at the location, in its time zone. `None` if
+    /// [`DailyVariable::Sunrise`] wasn't requested, `date` isn't covered or
+    /// the sun doesn't rise.
+    pub fn sunrise_on(&self, date: NaiveDate) -> Option<DateTime<FixedOffset>> {
+        let daily = self.daily.as_ref()?;
+        self.daily_time_on(date, daily.sunrise.as_ref()?)
+    }
+
+    /// Sunset on `date` at the location, see [`Forecast::sunrise_on`].
+    pub fn sunset_on(&self, date: NaiveDate) -> Option<DateTime<FixedOffset>> {
+        let daily = self.daily.as_ref()?;
+        self.daily_time_on(date, daily.sunset.as_ref()?)
+    }
+
+    /// Today's sunrise at the location, see [`Forecast::sunrise_on`].
+    pub fn today_sunrise(&self) -> Option<DateTime<FixedOffset>> {
+        self.sunrise_on(self.today()?)
+    }
+
+    /// Today's sunset at the location, see [`Forecast::sunrise_on`].
+    pub fn today_sunset(&self) -> Option<DateTime<FixedOffset>> {
+        self.sunset_on(self.today()?)
+    }
+
+    /// The current date at the location.
+    fn today(&self) -> Option<NaiveDate> {
+        Some(Utc::now().with_timezone(&self.utc_offset()?).date_naive())
+    }
+
+    fn daily_time_on(
+        &self,
+        date: NaiveDate,
+        times: &[Option<String>],
+    ) -> Option<DateTime<FixedOffset>> {
+        let date = date.format("%Y-%m-%d").to_string();
+        let index = self.daily.as_ref()?.time.iter().position(|d| *d == date)?;
+
+        self.local_time(times.get(index)?.as_ref()?)
+    }
+
+    /// Time of the `index`th 15-minutely value, in the location's time zone.
+    pub fn local_minutely_15_time_of(&self, index: usize) -> Option<DateTime<FixedOffset>> {
+        self.local_time(self.minutely_15.as_ref()?.time.get(index)?)
+    }
 
-        serializer.serialize_str(&s)
-    } else {
-        serializer.serialize_none()
+    /// Time of the current conditions, in the location's time zone.
+    pub fn current_local_time(&self) -> Option<DateTime<FixedOffset>> {
+        self.local_time(&self.current.as_ref()?.time)
     }
 }
+
+#[derive(Display, EnumString, Clone, Copy, Debug, Hash, PartialEq, Eq, DeserializeFromStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum MarineVariable {
+    WaveHeight,
+    WaveDirection,
+    WavePeriod,
+    WavePeakPeriod,
+    WindWaveHeight,
+    WindWaveDirection,
+    WindWavePeriod,
+    WindWavePeakPeriod,
+    SwellWaveHeight,
+    SwellWaveDirection,
+    SwellWavePeriod,
+    SwellWavePeakPeriod,
+    SecondarySwellWaveHeight,
+    SecondarySwellWaveDirection,
+    SecondarySwellWavePeriod,
+    OceanCurrentVelocity,
+    OceanCurrentDirection,
+    SeaSurfaceTemperature,
+    SeaLevelHeightMsl,
+    /// NOTE: Not a valid variable, only found within `.hourly_units`/`.current_units`
+    Time,
+    /// NOTE: Not a valid variable, only found within `.current_units`
+    Interval,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LengthUnit {
+    Metric,
+    Imperial,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Default, Clone)]
+pub struct MarineOptions {
+    #[serde(serialize_with = "fjordgard_common::csv")]
+    pub hourly: Option<Vec<MarineVariable>>,
+    #[serde(serialize_with = "fjordgard_common::csv")]
+    pub current: Option<Vec<MarineVariable>>,
+    pub length_unit: Option<LengthUnit>,
+    pub wind_speed_unit: Option<SpeedUnit>,
+    pub time_format: Option<TimeFormat>,
+    pub timezone: Option<String>,
+    pub past_days: Option<usize>,
+    pub forecast_days: Option<usize>,
+    pub forecast_hours: Option<usize>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub cell_selection: Option<CellSelection>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MarineHourlyData {
+    pub time: Vec<String>,
+    /// Values are `None` where there is no sea, such as over land.
+    #[serde(flatten)]
+    pub data: HashMap<MarineVariable, Vec<Option<f64>>>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MarineCurrentData {
+    pub time: String,
+    pub interval: usize,
+    /// Variables Open-Meteo has no data for are left out.
+    #[serde(flatten, deserialize_with = "without_nulls")]
+    pub data: HashMap<MarineVariable, f64>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MarineForecast {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub utc_offset_seconds: isize,
+    pub timezone: String,
+    pub timezone_abbreviation: String,
+    pub hourly: Option<MarineHourlyData>,
+    pub hourly_units: Option<HashMap<MarineVariable, String>>,
+    pub current: Option<MarineCurrentData>,
+    pub current_units: Option<HashMap<MarineVariable, String>>,
+}
+
+#[derive(Display, EnumString, Clone, Copy, Debug, Hash, PartialEq, Eq, DeserializeFromStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum AirQualityVariable {
+    Pm10,
+    #[strum(to_string = "pm2_5")]
+    Pm2_5,
+    CarbonMonoxide,
+    CarbonDioxide,
+    NitrogenDioxide,
+    SulphurDioxide,
+    Ozone,
+    AerosolOpticalDepth,
+    Dust,
+    UvIndex,
+    UvIndexClearSky,
+    Ammonia,
+    Methane,
+    AlderPollen,
+    BirchPollen,
+    GrassPollen,
+    MugwortPollen,
+    OlivePollen,
+    RagweedPollen,
+    EuropeanAqi,
+    #[strum(to_string = "european_aqi_pm2_5")]
+    EuropeanAqiPm2_5,
+    #[strum(to_string = "european_aqi_pm10")]
+    EuropeanAqiPm10,
+    EuropeanAqiNitrogenDioxide,
+    EuropeanAqiOzone,
+    EuropeanAqiSulphurDioxide,
+    UsAqi,
+    #[strum(to_string = "us_aqi_pm2_5")]
+    UsAqiPm2_5,
+    #[strum(to_string = "us_aqi_pm10")]
+    UsAqiPm10,
+    UsAqiNitrogenDioxide,
+    UsAqiOzone,
+    UsAqiSulphurDioxide,
+    UsAqiCarbonMonoxide,
+    /// NOTE: Not a valid variable, only found within `.hourly_units`/`.current_units`
+    Time,
+    /// NOTE: Not a valid variable, only found within `.current_units`
+    Interval,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AirQualityDomain {
+    Auto,
+    CamsEurope,
+    CamsGlobal,
+}
+
+#[serde_with::skip_serializing_none]
+#[derive(Serialize, Default, Clone)]
+pub struct AirQualityOptions {
+    #[serde(serialize_with = "fjordgard_common::csv")]
+    pub hourly: Option<Vec<AirQualityVariable>>,
+    #[serde(serialize_with = "fjordgard_common::csv")]
+    pub current: Option<Vec<AirQualityVariable>>,
+    pub domains: Option<AirQualityDomain>,
+    pub time_format: Option<TimeFormat>,
+    pub timezone: Option<String>,
+    pub past_days: Option<usize>,
+    pub forecast_days: Option<usize>,
+    pub forecast_hours: Option<usize>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub cell_selection: Option<CellSelection>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AirQualityHourlyData {
+    pub time: Vec<String>,
+    /// Values are `None` where a variable isn't covered, such as pollen
+    /// outside Europe.
+    #[serde(flatten)]
+    pub data: HashMap<AirQualityVariable, Vec<Option<f64>>>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AirQualityCurrentData {
+    pub time: String,
+    pub interval: usize,
+    /// Variables Open-Meteo has no data for are left out.
+    #[serde(flatten, deserialize_with = "without_nulls")]
+    pub data: HashMap<AirQualityVariable, f64>,
+}
+
+/// Current weather and air quality of a location, see
+/// [`MeteoClient::current_conditions`](crate::MeteoClient::current_conditions).
+#[derive(Debug, Clone)]
+pub struct Conditions {
+    pub forecast: Forecast,
+    /// `None` if air quality couldn't be fetched.
+    pub air_quality: Option<AirQuality>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AirQuality {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub utc_offset_seconds: isize,
+    pub timezone: String,
+    pub timezone_abbreviation: String,
+    pub hourly: Option<AirQualityHourlyData>,
+    pub hourly_units: Option<HashMap<AirQualityVariable, String>>,
+    pub current: Option<AirQualityCurrentData>,
+    pub current_units: Option<HashMap<AirQualityVariable, String>>,
+}
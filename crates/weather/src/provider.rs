@@ -0,0 +1,184 @@
+use std::{future::Future, pin::Pin, sync::Mutex};
+
+use crate::{
+    Error, MeteoClient,
+    error::Result,
+    model::{Forecast, ForecastOptions},
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+#[cfg(target_arch = "wasm32")]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
+
+/// A source of forecasts.
+pub trait WeatherProvider: Send + Sync {
+    /// Name used in diagnostics.
+    fn name(&self) -> &str;
+
+    fn forecast<'a>(
+        &'a self,
+        latitude: f64,
+        longitude: f64,
+        opt: Option<ForecastOptions>,
+    ) -> BoxFuture<'a, Result<Forecast>>;
+}
+
+impl WeatherProvider for MeteoClient {
+    fn name(&self) -> &str {
+        "open-meteo"
+    }
+
+    fn forecast<'a>(
+        &'a self,
+        latitude: f64,
+        longitude: f64,
+        opt: Option<ForecastOptions>,
+    ) -> BoxFuture<'a, Result<Forecast>> {
+        Box::pin(self.forecast_single(latitude, longitude, opt))
+    }
+}
+
+/// Tries each provider in order, returning the first success.
+///
+/// Only transient failures (see [`Error::is_transient`]) move on to the next
+/// provider, bad input is returned as is. If every provider fails the first
+/// error is returned, as fallbacks such as caches rarely say much.
+pub struct FallbackProvider {
+    providers: Vec<Box<dyn WeatherProvider>>,
+    last_answered: Mutex<Option<String>>,
+}
+
+impl FallbackProvider {
+    pub fn new(primary: impl WeatherProvider + 'static) -> Self {
+        Self {
+            providers: vec![Box::new(primary)],
+            last_answered: Mutex::new(None),
+        }
+    }
+
+    pub fn with_fallback(mut self, provider: impl WeatherProvider + 'static) -> Self {
+        self.providers.push(Box::new(provider));
+        self
+    }
+
+    /// Name of the provider that answered the last successful request.
+    pub fn last_answered(&self) -> Option<String> {
+        self.last_answered.lock().unwrap().clone()
+    }
+
+    async fn forecast_chain(
+        &self,
+        latitude: f64,
+        longitude: f64,
+        opt: Option<ForecastOptions>,
+    ) -> Result<Forecast> {
+        let mut first_err = None;
+
+        for provider in self.providers.iter() {
+            match provider.forecast(latitude, longitude, opt.clone()).await {
+                Ok(forecast) => {
+                    *self.last_answered.lock().unwrap() = Some(provider.name().to_string());
+                    return Ok(forecast);
+                }
+                Err(e) if e.is_transient() => {
+                    first_err.get_or_insert(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(first_err.unwrap_or(Error::Unavailable))
+    }
+}
+
+impl WeatherProvider for FallbackProvider {
+    fn name(&self) -> &str {
+        "fallback"
+    }
+
+    fn forecast<'a>(
+        &'a self,
+        latitude: f64,
+        longitude: f64,
+        opt: Option<ForecastOptions>,
+    ) -> BoxFuture<'a, Result<Forecast>> {
+        Box::pin(self.forecast_chain(latitude, longitude, opt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed {
+        name: &'static str,
+        result: fn() -> Result<Forecast>,
+    }
+
+    impl Fixed {
+        fn new(name: &'static str, result: fn() -> Result<Forecast>) -> Self {
+            Self { name, result }
+        }
+    }
+
+    impl WeatherProvider for Fixed {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn forecast<'a>(
+            &'a self,
+            _latitude: f64,
+            _longitude: f64,
+            _opt: Option<ForecastOptions>,
+        ) -> BoxFuture<'a, Result<Forecast>> {
+            let res = (self.result)();
+            Box::pin(async move { res })
+        }
+    }
+
+    fn forecast() -> Result<Forecast> {
+        Ok(serde_json::from_str(
+            r#"{
+                "latitude": 51.5,
+                "longitude": -0.12,
+                "elevation": 23.0,
+                "utc_offset_seconds": 0,
+                "timezone": "GMT",
+                "timezone_abbreviation": "GMT"
+            }"#,
+        )?)
+    }
+
+    fn unavailable() -> Result<Forecast> {
+        Err(Error::Unavailable)
+    }
+
+    fn bad_input() -> Result<Forecast> {
+        Err(Error::meteo(
+            "Latitude must be in range of -90 to 90°.".to_string(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn falls_back_on_transient() {
+        let provider = FallbackProvider::new(Fixed::new("primary", unavailable))
+            .with_fallback(Fixed::new("secondary", forecast));
+
+        provider.forecast(51.5, -0.12, None).await.unwrap();
+
+        assert_eq!(provider.last_answered(), Some("secondary".to_string()));
+    }
+
+    #[tokio::test]
+    async fn stops_on_bad_input() {
+        let provider = FallbackProvider::new(Fixed::new("primary", bad_input))
+            .with_fallback(Fixed::new("secondary", forecast));
+
+        let err = provider.forecast(91.0, -0.12, None).await.unwrap_err();
+
+        assert!(matches!(err, Error::Meteo { .. }));
+        assert_eq!(provider.last_answered(), None);
+    }
+}
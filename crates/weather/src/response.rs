@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+/// Body of every Open-Meteo response, errors come with a reason rather than
+/// the requested data.
+///
+/// Public only within the crate, unless re-exported by the `testing` feature.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum MeteoResponse {
+    Error { reason: String },
+    Success(serde_json::Value),
+}
@@ -1,44 +1,371 @@
+use std::time::Duration;
+
 use fjordgard_unsplash::{
-    UnsplashClient,
-    model::{Collection, CollectionPhotos, CollectionPhotosOptions, Format, PhotoFetchOptions},
+    Error as UnsplashError, RetryPolicy, UnsplashClient,
+    model::{
+        Attribution, CollectionPhotos, CollectionPhotosOptions, Format, Photo, PhotoFetchOptions,
+        TopicPhotosOptions,
+    },
 };
+use iced::futures::SinkExt;
 use iced::{
-    Color, ContentFit, Element, Length, Size, Task,
-    widget::{button, container, image, row, stack, text},
+    Color, ContentFit, Degrees, Element, Length, Size, Subscription, Task, clipboard,
+    gradient::{self, Gradient},
+    time::{self, Instant},
+    widget::{button, column, container, image, mouse_area, progress_bar, row, stack, text},
 };
-use log::{debug, error};
+use log::{debug, error, warn};
+#[cfg(not(target_arch = "wasm32"))]
+use rfd::AsyncFileDialog;
+
+use crate::config::{BackgroundFit, BackgroundMode, Config, PhotoOrder};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::local_color::LocalColorCache;
+
+const BLUR_PLACEHOLDER_WIDTH: u32 = 32;
+/// Side of the thumbnail local images are blurred at for letterboxing.
+#[cfg(not(target_arch = "wasm32"))]
+const BACKDROP_SIZE: u32 = 64;
+#[cfg(not(target_arch = "wasm32"))]
+const BACKDROP_BLUR: f32 = 3.0;
+/// Images are scaled down until the blur radius is at most this many pixels
+/// before blurring, which looks the same and is much faster.
+#[cfg(not(target_arch = "wasm32"))]
+const BLUR_WORKING_RADIUS: f32 = 4.0;
+/// Generous as it also covers downloading full size photos.
+const UNSPLASH_TIMEOUT: Duration = Duration::from_secs(60);
+const UNSPLASH_CACHE_SIZE: usize = 10;
+/// Longest side of photos downloaded in data saver mode.
+const DATA_SAVER_MAX_SIDE: f32 = 1920.0;
+/// JPEG quality of photos downloaded in data saver mode.
+const DATA_SAVER_QUALITY: usize = 60;
+/// Unsplash topic shown when the configured collection or topic is empty,
+/// private or deleted. Topics are curated by Unsplash, so unlike collections
+/// they don't disappear.
+const FALLBACK_TOPIC: &str = "wallpapers";
+/// Photos fetched per page, the most Unsplash allows.
+const UNSPLASH_PER_PAGE: usize = 30;
+/// How long the window has to stay put before refetching the photo or saving
+/// its geometry.
+pub const RESIZE_DEBOUNCE: Duration = Duration::from_millis(500);
+/// How long status messages stay on screen.
+const STATUS_DURATION: Duration = Duration::from_secs(4);
+
+/// The 1-indexed page holding photo `index`, and its position on that page.
+fn page_position(index: usize, per_page: usize) -> (usize, usize) {
+    (index / per_page + 1, index % per_page)
+}
+
+fn blur_placeholder_size(photo: &Photo) -> (u32, u32) {
+    let height = (BLUR_PLACEHOLDER_WIDTH as f64 / photo.aspect_ratio()).round() as u32;
+
+    (BLUR_PLACEHOLDER_WIDTH, height.max(1))
+}
+
+/// The blur hash of `photo` decoded to a small image.
+fn blur_placeholder(photo: &Photo) -> Option<image::Handle> {
+    let (width, height) = blur_placeholder_size(photo);
+
+    match photo.blur_placeholder(width, height) {
+        Ok(pixels) => Some(image::Handle::from_rgba(width, height, pixels)),
+        Err(e) => {
+            debug!("failed to decode blur hash: {e}");
+            None
+        }
+    }
+}
+
+/// A small blurred copy of a local image, scaled up behind it to fill the
+/// letterbox bars.
+#[cfg(not(target_arch = "wasm32"))]
+fn blurred_backdrop(bytes: &[u8]) -> anyhow::Result<image::Handle> {
+    let backdrop = ::image::load_from_memory(bytes)?
+        .thumbnail(BACKDROP_SIZE, BACKDROP_SIZE)
+        .blur(BACKDROP_BLUR)
+        .to_rgba8();
+
+    Ok(image::Handle::from_rgba(
+        backdrop.width(),
+        backdrop.height(),
+        backdrop.into_raw(),
+    ))
+}
+
+/// Decode `bytes` and blur it by `radius` pixels.
+#[cfg(not(target_arch = "wasm32"))]
+fn blurred_image(bytes: &[u8], radius: f32) -> anyhow::Result<image::Handle> {
+    let image = ::image::load_from_memory(bytes)?;
+
+    let scale = (radius / BLUR_WORKING_RADIUS).max(1.0);
+    let image = if scale > 1.0 {
+        let width = ((image.width() as f32 / scale) as u32).max(1);
+        let height = ((image.height() as f32 / scale) as u32).max(1);
+
+        image.resize(width, height, ::image::imageops::FilterType::Triangle)
+    } else {
+        image
+    };
+
+    let blurred = image.blur(radius / scale).to_rgba8();
+
+    Ok(image::Handle::from_rgba(
+        blurred.width(),
+        blurred.height(),
+        blurred.into_raw(),
+    ))
+}
+
+/// Parse a gradient in the form `45deg,#ff0000,#0000ff`, with between two
+/// and eight evenly spaced color stops.
+pub fn parse_gradient(s: &str) -> Option<Gradient> {
+    let mut parts = s.split(',').map(str::trim);
+
+    let angle = parts.next()?;
+    let angle: f32 = angle.strip_suffix("deg").unwrap_or(angle).parse().ok()?;
+
+    let colors = parts.map(Color::parse).collect::<Option<Vec<_>>>()?;
+
+    if !(2..=8).contains(&colors.len()) {
+        return None;
+    }
+
+    let last = (colors.len() - 1) as f32;
+    let linear = colors.into_iter().enumerate().fold(
+        gradient::Linear::new(Degrees(angle)),
+        |linear, (i, color)| linear.add_stop(i as f32 / last, color),
+    );
+
+    Some(linear.into())
+}
+
+/// The mean of `colors`, or `None` if there are none.
+fn mean_color(colors: impl IntoIterator<Item = Color>) -> Option<Color> {
+    let (sum, count) = colors.into_iter().fold(([0.0; 3], 0), |(sum, count), c| {
+        ([sum[0] + c.r, sum[1] + c.g, sum[2] + c.b], count + 1)
+    });
+
+    (count > 0).then(|| {
+        let n = count as f32;
+        Color::from_rgb(sum[0] / n, sum[1] / n, sum[2] / n)
+    })
+}
+
+/// Black or white, whichever contrasts more with `background`.
+fn contrasting_text(background: Color) -> Color {
+    let [r, g, b, _] = background.into_linear();
+    let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+
+    // where the contrast ratios against black and white are equal
+    if luminance > 0.179 {
+        Color::BLACK
+    } else {
+        Color::WHITE
+    }
+}
+
+/// A photo downloaded ahead of time so advancing to it is instant.
+#[derive(Debug, Clone)]
+pub struct Prefetched {
+    index: usize,
+    size: Size,
+    page_photos: CollectionPhotos,
+    handle: image::Handle,
+    /// The photo before blurring, see [`BackgroundHandle::image_bytes`].
+    #[cfg(not(target_arch = "wasm32"))]
+    bytes: Option<Vec<u8>>,
+}
+
+/// Where Unsplash photos are picked from.
+#[derive(Debug, Clone)]
+pub enum UnsplashSource {
+    Collection(String),
+    Topic(String),
+}
+
+impl UnsplashSource {
+    fn id(&self) -> &str {
+        match self {
+            Self::Collection(id) | Self::Topic(id) => id,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Collection(_) => "collection",
+            Self::Topic(_) => "topic",
+        }
+    }
 
-use crate::config::{BackgroundMode, Config};
+    /// Resolve the source, returning its id along with its photo count. The
+    /// count is `0` for sources photos can't be listed from, including ones
+    /// that don't exist.
+    async fn load(self, client: &UnsplashClient) -> Result<(Self, usize), UnsplashError> {
+        match self {
+            Self::Collection(id) => match client.collection(&id).await {
+                Ok(collection) if collection.is_accessible() => {
+                    Ok((Self::Collection(collection.id), collection.total_photos))
+                }
+                Ok(_) | Err(UnsplashError::NotFound { .. }) => Ok((Self::Collection(id), 0)),
+                Err(e) => Err(e),
+            },
+            Self::Topic(slug) => match client.topic(&slug).await {
+                Ok(topic) => Ok((Self::Topic(topic.id), topic.total_photos)),
+                Err(UnsplashError::NotFound { .. }) => Ok((Self::Topic(slug), 0)),
+                Err(e) => Err(e),
+            },
+        }
+    }
+
+    /// Fetch the 1-indexed `page` of photos, [`UNSPLASH_PER_PAGE`] at a time.
+    async fn page(
+        &self,
+        client: &UnsplashClient,
+        page: usize,
+        order: PhotoOrder,
+    ) -> Result<CollectionPhotos, UnsplashError> {
+        match self {
+            Self::Collection(id) => {
+                client
+                    .collection_photos(
+                        id,
+                        Some(CollectionPhotosOptions {
+                            page: Some(page),
+                            per_page: Some(UNSPLASH_PER_PAGE),
+                            order_by: order.order_by(),
+                            ..Default::default()
+                        }),
+                    )
+                    .await
+            }
+            Self::Topic(id) => {
+                client
+                    .topic_photos(
+                        id,
+                        Some(TopicPhotosOptions {
+                            page: Some(page),
+                            per_page: Some(UNSPLASH_PER_PAGE),
+                            order_by: order.order_by(),
+                            ..Default::default()
+                        }),
+                    )
+                    .await
+            }
+        }
+    }
+}
+
+/// Events of downloading the current Unsplash photo.
+enum Download {
+    /// Fraction of the photo downloaded so far.
+    Progress(f32),
+    Done(Result<Vec<u8>, String>),
+}
 
 pub struct UnsplashState {
-    collection: String,
+    source: UnsplashSource,
     current: usize,
     total: usize,
     paused: bool,
 
     current_page_photos: Option<CollectionPhotos>,
     current_page: usize,
+
+    prefetched: Option<Prefetched>,
+}
+
+impl UnsplashState {
+    fn current_photo(&self) -> Option<&Photo> {
+        self.current_page_photos.as_ref().and_then(|c| {
+            c.photos
+                .get(page_position(self.current, UNSPLASH_PER_PAGE).1)
+        })
+    }
+
+    /// Index `direction` photos away from the current one, wrapping around
+    /// at either end.
+    fn step(&self, direction: isize) -> usize {
+        if self.total == 0 {
+            return 0;
+        }
+
+        (self.current as isize + direction).rem_euclid(self.total as isize) as usize
+    }
 }
 
 pub struct BackgroundHandle {
     pub mode: BackgroundMode,
     background: String,
     size: Size,
+    pending_resize: Option<(Size, Instant)>,
+    overlay_opacity: f32,
+    /// Fixed text color, picked from the background's brightness if unset.
+    text_color: Option<Color>,
+    show_photo_info: bool,
+    fit: BackgroundFit,
+    data_saver: bool,
+    order: PhotoOrder,
 
     image_handle: Option<image::Handle>,
+    /// Blurred copy of the image, drawn behind it with [`BackgroundFit::Contain`].
+    backdrop: Option<image::Handle>,
+    /// Shown while there is no image, before even the blurred preview.
+    placeholder_color: Option<Color>,
+    /// Average color of the current image, used to pick a readable text color.
+    image_color: Option<Color>,
+    /// Radius in pixels image backgrounds are blurred by.
+    #[cfg(not(target_arch = "wasm32"))]
+    blur: f32,
+    /// The current image before blurring, kept to blur it again when the
+    /// radius changes. Unblurred images keep their bytes in `image_handle`.
+    #[cfg(not(target_arch = "wasm32"))]
+    image_bytes: Option<Vec<u8>>,
 
     unsplash_key: Option<String>,
+    /// See [`Config::mock`].
+    mock: bool,
     unsplash_client: Option<UnsplashClient>,
     unsplash_state: Option<UnsplashState>,
+
+    /// Id of the latest background request, responses to older ones are
+    /// dropped so they can't replace newer results.
+    request: u64,
+    /// Fraction of the next image downloaded, while one is downloading.
+    download_progress: Option<f32>,
+    /// Message shown until the instant it expires.
+    status: Option<(String, Instant)>,
+    /// Explains why there is no background, shown until the next refresh.
+    notice: Option<String>,
+    /// The configured Unsplash source couldn't be used, so photos come from
+    /// [`FALLBACK_TOPIC`].
+    using_fallback: bool,
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    BackgroundRead(Result<Vec<u8>, String>),
-    UnsplashCollection(Box<Result<Collection, String>>),
-    UnsplashCollectionPhotos(Result<CollectionPhotos, String>),
+    BackgroundRead(u64, Result<Vec<u8>, String>),
+    UnsplashSourceLoaded(u64, Result<(UnsplashSource, usize), String>),
+    UnsplashCollectionPhotos(u64, Result<CollectionPhotos, String>),
+    UnsplashDownloadTracked(Result<(), String>),
+    UnsplashPrefetched(u64, Result<Box<Prefetched>, String>),
     RequestUnsplash(isize),
     PauseUnsplash,
+    ResizeTick(Instant),
+    UnsplashResized(usize, Result<Vec<u8>, String>),
+    DownloadProgress(u64, f32),
+    SaveWallpaper,
+    #[cfg(not(target_arch = "wasm32"))]
+    WallpaperSaved(Result<bool, String>),
+    StatusTick(Instant),
+    #[cfg(not(target_arch = "wasm32"))]
+    LocalColor(u64, Option<[u8; 3]>),
+    #[cfg(not(target_arch = "wasm32"))]
+    LocalColorCached(u64, Result<[u8; 3], String>),
+    #[cfg(not(target_arch = "wasm32"))]
+    LocalBackdrop(u64, Result<image::Handle, String>),
+    #[cfg(not(target_arch = "wasm32"))]
+    Blurred(u64, Result<image::Handle, String>),
+    OpenPhotoPage,
+    OpenAuthorProfile,
     OpenUrl(String),
 }
 
@@ -48,12 +375,33 @@ impl BackgroundHandle {
             mode: config.background_mode,
             background: config.background.clone(),
             size,
+            pending_resize: None,
+            overlay_opacity: config.overlay_opacity,
+            text_color: config.text_color(),
+            show_photo_info: config.show_photo_info,
+            fit: config.background_fit,
+            data_saver: config.data_saver,
+            order: config.photo_order,
 
             image_handle: None,
+            backdrop: None,
+            placeholder_color: None,
+            image_color: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            blur: config.background_blur,
+            #[cfg(not(target_arch = "wasm32"))]
+            image_bytes: None,
 
             unsplash_key: config.unsplash_key.clone(),
+            mock: config.mock,
             unsplash_client: None,
             unsplash_state: None,
+
+            request: 0,
+            download_progress: None,
+            status: None,
+            notice: None,
+            using_fallback: false,
         };
 
         let task = handle.refresh(true);
@@ -62,20 +410,365 @@ impl BackgroundHandle {
     }
 
     pub fn load_config(&mut self, config: &Config, size: Size) -> Task<Message> {
+        // switching between collections and topics, or reordering, needs a fresh
+        // state too
+        let source_changed = self.mode != config.background_mode
+            || self.background != config.background
+            || self.order != config.photo_order;
+
+        // notices are about the Unsplash source, refreshing it clears them
+        if self.mode != config.background_mode {
+            self.notice = None;
+        }
+
         self.mode = config.background_mode;
         self.background = config.background.clone();
-        self.size = size;
+        self.order = config.photo_order;
+        self.set_size(size);
+        self.overlay_opacity = config.overlay_opacity;
+        self.text_color = config.text_color();
+        self.show_photo_info = config.show_photo_info;
+        self.fit = config.background_fit;
 
-        if self.unsplash_key != config.unsplash_key {
+        if self.data_saver != config.data_saver {
+            self.data_saver = config.data_saver;
+
+            // downloaded at the old quality
+            if let Some(state) = &mut self.unsplash_state {
+                state.prefetched = None;
+            }
+        }
+
+        #[cfg_attr(target_arch = "wasm32", allow(unused_mut))]
+        let mut reblur = Task::none();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.blur != config.background_blur {
+            self.blur = config.background_blur;
+
+            // blurred with the old radius
+            if let Some(state) = &mut self.unsplash_state {
+                state.prefetched = None;
+            }
+
+            // local images are read again on refresh anyway
+            if self.mode.is_unsplash()
+                && let Some(bytes) = self.unblurred_bytes()
+            {
+                reblur = self.show_image(bytes);
+            }
+        }
+
+        let refresh = if self.unsplash_key != config.unsplash_key || source_changed {
             self.unsplash_key = config.unsplash_key.clone();
             self.unsplash_state = None;
             self.refresh(true)
         } else {
             self.refresh(false)
+        };
+
+        Task::batch([reblur, refresh])
+    }
+
+    pub fn set_show_photo_info(&mut self, show: bool) {
+        self.show_photo_info = show;
+    }
+
+    /// Whether Unsplash rotation is paused.
+    pub fn unsplash_paused(&self) -> bool {
+        self.unsplash_state.as_ref().is_some_and(|s| s.paused)
+    }
+
+    /// One-based index of the current Unsplash photo and the photo count.
+    pub fn unsplash_position(&self) -> Option<(usize, usize)> {
+        self.unsplash_state
+            .as_ref()
+            .map(|s| (s.current + 1, s.total))
+    }
+
+    /// Color of text drawn over the background, either the configured one or
+    /// black or white depending on how bright the background is.
+    pub fn text_color(&self) -> Color {
+        self.text_color
+            .unwrap_or_else(|| self.average_color().map_or(Color::WHITE, contrasting_text))
+    }
+
+    /// Average color of the background as shown, overlay included.
+    fn average_color(&self) -> Option<Color> {
+        let color = match self.mode {
+            BackgroundMode::Solid => Color::parse(&self.background),
+            BackgroundMode::Gradient => match parse_gradient(&self.background)? {
+                Gradient::Linear(linear) => {
+                    mean_color(linear.stops.into_iter().flatten().map(|s| s.color))
+                }
+            },
+            _ => self.image_color,
+        }?;
+
+        // the overlay is black, blended over the background
+        let keep = 1.0 - self.overlay_opacity;
+        Some(Color::from_rgb(
+            color.r * keep,
+            color.g * keep,
+            color.b * keep,
+        ))
+    }
+
+    /// Note the window changed size, the photo is refetched once it settles.
+    pub fn resize(&mut self, size: Size) {
+        self.pending_resize = Some((size, Instant::now()));
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let resize = if self.pending_resize.is_some() {
+            time::every(Duration::from_millis(100)).map(Message::ResizeTick)
+        } else {
+            Subscription::none()
+        };
+
+        let status = if self.status.is_some() {
+            time::every(Duration::from_millis(500)).map(Message::StatusTick)
+        } else {
+            Subscription::none()
+        };
+
+        Subscription::batch([resize, status])
+    }
+
+    /// Open a link from the current Unsplash photo's attribution.
+    fn open_attribution(&self, link: impl FnOnce(Attribution) -> String) -> Task<Message> {
+        match self.unsplash_state.as_ref().and_then(|s| s.current_photo()) {
+            Some(photo) => Task::done(Message::OpenUrl(link(photo.attribution()))),
+            None => Task::none(),
+        }
+    }
+
+    /// Show `status` over the background for a few seconds.
+    pub fn show_status(&mut self, status: impl Into<String>) {
+        self.status = Some((status.into(), Instant::now() + STATUS_DURATION));
+    }
+
+    /// Fetch photos at `size` from now on, without refetching the current one.
+    pub fn set_size(&mut self, size: Size) {
+        if self.size != size
+            && let Some(state) = &mut self.unsplash_state
+        {
+            // downloaded at the old size
+            state.prefetched = None;
+        }
+
+        self.size = size;
+    }
+
+    /// Options fetching a photo for a window of `size`. In data saver mode
+    /// that's a compressed JPEG no larger than [`DATA_SAVER_MAX_SIDE`].
+    fn photo_options(size: Size, data_saver: bool) -> PhotoFetchOptions {
+        if data_saver {
+            let scale = (DATA_SAVER_MAX_SIDE / size.width.max(size.height)).min(1.0);
+
+            return PhotoFetchOptions {
+                fm: Some(Format::Jpg),
+                q: Some(DATA_SAVER_QUALITY),
+                w: Some((size.width * scale).round().into()),
+                h: Some((size.height * scale).round().into()),
+                ..Default::default()
+            };
+        }
+
+        PhotoFetchOptions {
+            fm: Some(Format::Png),
+            w: Some(size.width.round().into()),
+            h: Some(size.height.round().into()),
+            ..Default::default()
+        }
+    }
+
+    /// Called once the current Unsplash photo is on screen.
+    fn unsplash_displayed(&mut self) -> Task<Message> {
+        match (&self.unsplash_client, &self.unsplash_state) {
+            (Some(client), Some(state)) => match state.current_photo() {
+                Some(photo) => {
+                    let client = client.clone();
+                    let photo = photo.clone();
+
+                    let track =
+                        Task::future(
+                            async move { client.track_download(&photo).await.map(|_| ()) },
+                        )
+                        .map(|r| Message::UnsplashDownloadTracked(r.map_err(|e| e.to_string())));
+
+                    Task::batch([track, self.prefetch_next()])
+                }
+                None => Task::none(),
+            },
+            _ => Task::none(),
         }
     }
 
-    fn refresh(&mut self, refresh_unsplash: bool) -> Task<Message> {
+    /// Download the current Unsplash photo at the current size, reporting
+    /// progress as it goes.
+    fn download_current(&self) -> Task<Download> {
+        let (Some(client), Some(photo)) = (
+            &self.unsplash_client,
+            self.unsplash_state.as_ref().and_then(|s| s.current_photo()),
+        ) else {
+            return Task::none();
+        };
+
+        let client = client.clone();
+        let photo = photo.clone();
+        let size = self.size;
+        let data_saver = self.data_saver;
+
+        Task::stream(iced::stream::channel(10, move |mut output| async move {
+            let mut reported = 0.0;
+
+            let res = client
+                .download_photo_with_progress(
+                    &photo,
+                    Some(Self::photo_options(size, data_saver)),
+                    |downloaded, total| {
+                        let Some(total) = total.filter(|t| *t > 0) else {
+                            return;
+                        };
+
+                        // only report whole percents to not flood the update loop
+                        let progress = downloaded as f32 / total as f32;
+                        if progress - reported >= 0.01 {
+                            reported = progress;
+                            let _ = output.try_send(Download::Progress(progress));
+                        }
+                    },
+                )
+                .await
+                .map(|b| b.to_vec())
+                .map_err(|e| e.to_string());
+
+            let _ = output.send(Download::Done(res)).await;
+        }))
+    }
+
+    /// Download the photo after the current one into the photo cache.
+    fn prefetch_next(&self) -> Task<Message> {
+        let (Some(client), Some(state)) = (&self.unsplash_client, &self.unsplash_state) else {
+            return Task::none();
+        };
+
+        // don't spend requests while the user wants the current photo
+        if state.paused {
+            return Task::none();
+        }
+
+        let index = state.step(1);
+        let size = self.size;
+        let data_saver = self.data_saver;
+        let request = self.request;
+        #[cfg(not(target_arch = "wasm32"))]
+        let blur = self.blur;
+
+        if state
+            .prefetched
+            .as_ref()
+            .is_some_and(|p| p.index == index && p.size == size)
+        {
+            return Task::none();
+        }
+
+        let (page, offset) = page_position(index, UNSPLASH_PER_PAGE);
+        let current_page_photos = state
+            .current_page_photos
+            .clone()
+            .filter(|_| page == state.current_page);
+        let source = state.source.clone();
+        let client = client.clone();
+        let order = self.order;
+
+        Task::future(async move {
+            let page_photos = match current_page_photos {
+                Some(photos) => photos,
+                None => source.page(&client, page, order).await?,
+            };
+
+            let Some(photo) = page_photos.photos.get(offset) else {
+                anyhow::bail!("photo {index} not found");
+            };
+
+            let bytes = client
+                .download_photo(photo, Some(Self::photo_options(size, data_saver)))
+                .await?;
+
+            #[cfg(not(target_arch = "wasm32"))]
+            let (handle, bytes) = if blur > 0.0 {
+                let bytes = Vec::from(bytes);
+                tokio::task::spawn_blocking(move || {
+                    blurred_image(&bytes, blur).map(|handle| (handle, Some(bytes)))
+                })
+                .await??
+            } else {
+                (image::Handle::from_bytes(bytes), None)
+            };
+            #[cfg(target_arch = "wasm32")]
+            let handle = image::Handle::from_bytes(bytes);
+
+            anyhow::Ok(Box::new(Prefetched {
+                index,
+                size,
+                handle,
+                page_photos,
+                #[cfg(not(target_arch = "wasm32"))]
+                bytes,
+            }))
+        })
+        .map(move |r| Message::UnsplashPrefetched(request, r.map_err(|e| e.to_string())))
+    }
+
+    /// Show `bytes` as the background, blurred first if configured.
+    fn show_image(&mut self, bytes: Vec<u8>) -> Task<Message> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            // keep showing the previous image until the blurred one is ready,
+            // rather than flashing the sharp one
+            if self.blur > 0.0 {
+                self.image_bytes = Some(bytes.clone());
+
+                let request = self.request;
+                let blur = self.blur;
+
+                return Task::future(async move {
+                    tokio::task::spawn_blocking(move || blurred_image(&bytes, blur)).await?
+                })
+                .map(move |r| Message::Blurred(request, r.map_err(|e| e.to_string())));
+            }
+
+            self.image_bytes = None;
+        }
+
+        self.image_handle = Some(image::Handle::from_bytes(bytes));
+
+        Task::none()
+    }
+
+    /// The current image as it was before blurring.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn unblurred_bytes(&self) -> Option<Vec<u8>> {
+        self.image_bytes
+            .clone()
+            .or_else(|| match &self.image_handle {
+                Some(image::Handle::Bytes(_, bytes)) => Some(bytes.to_vec()),
+                _ => None,
+            })
+    }
+
+    fn next_request(&mut self) -> u64 {
+        self.download_progress = None;
+        self.request += 1;
+        self.request
+    }
+
+    /// Load the background again. `source_changed` is false when only other
+    /// settings changed, so Unsplash is left alone and the current local
+    /// image stays up while it's read again.
+    fn refresh(&mut self, source_changed: bool) -> Task<Message> {
         debug!(
             "refreshing background (mode={}, background={})",
             self.mode, &self.background
@@ -85,17 +778,49 @@ impl BackgroundHandle {
             #[cfg(not(target_arch = "wasm32"))]
             BackgroundMode::Local => {
                 let path = self.background.clone();
+                let request = self.next_request();
+
+                // show the cached color until the new image is read
+                if source_changed {
+                    self.image_handle = None;
+                    self.image_bytes = None;
+                    self.backdrop = None;
+                    self.placeholder_color = None;
+                    self.image_color = None;
+                }
 
-                Task::future(async move { tokio::fs::read(&path).await })
-                    .map(|r| Message::BackgroundRead(r.map_err(|e| e.to_string())))
+                Task::batch([
+                    Task::future(LocalColorCache::get(path.clone()))
+                        .map(move |c| Message::LocalColor(request, c)),
+                    Task::future(async move { tokio::fs::read(&path).await }).map(move |r| {
+                        Message::BackgroundRead(request, r.map_err(|e| e.to_string()))
+                    }),
+                ])
             }
-            BackgroundMode::Unsplash => {
-                if !refresh_unsplash {
+            BackgroundMode::Unsplash | BackgroundMode::UnsplashTopic => {
+                if !source_changed {
                     return Task::none();
                 }
 
-                if let Some(key) = &self.unsplash_key {
-                    self.unsplash_client = match UnsplashClient::new(key) {
+                self.notice = None;
+
+                if self.unsplash_key.is_some() || self.mock {
+                    let mut builder = UnsplashClient::builder().mock(self.mock);
+
+                    if let Some(key) = &self.unsplash_key {
+                        builder = builder.api_key(key);
+                    }
+
+                    let client = builder
+                        .retry_policy(RetryPolicy {
+                            max_retries: 3,
+                            ..Default::default()
+                        })
+                        .timeout(UNSPLASH_TIMEOUT)
+                        .build()
+                        .map(|c| c.with_cache(UNSPLASH_CACHE_SIZE));
+
+                    self.unsplash_client = match client {
                         Ok(c) => Some(c),
                         Err(e) => {
                             error!("failed to create Unsplash client: {e}");
@@ -104,12 +829,14 @@ impl BackgroundHandle {
                         }
                     };
 
-                    let collection = self.background.clone();
-                    let client = self.unsplash_client.clone().unwrap();
+                    let source = if self.mode == BackgroundMode::UnsplashTopic {
+                        UnsplashSource::Topic(self.background.clone())
+                    } else {
+                        UnsplashSource::Collection(self.background.clone())
+                    };
 
-                    Task::future(async move { client.collection(&collection).await }).map(|r| {
-                        Message::UnsplashCollection(Box::new(r.map_err(|e| e.to_string())))
-                    })
+                    self.using_fallback = false;
+                    self.load_unsplash_source(source)
                 } else {
                     Task::none()
                 }
@@ -118,95 +845,290 @@ impl BackgroundHandle {
         }
     }
 
+    fn load_unsplash_source(&mut self, source: UnsplashSource) -> Task<Message> {
+        let Some(client) = self.unsplash_client.clone() else {
+            return Task::none();
+        };
+        let request = self.next_request();
+
+        Task::future(async move { source.load(&client).await })
+            .map(move |r| Message::UnsplashSourceLoaded(request, r.map_err(|e| e.to_string())))
+    }
+
     pub fn update(&mut self, msg: Message) -> Task<Message> {
         match msg {
-            Message::BackgroundRead(res) => match res {
+            Message::BackgroundRead(request, _)
+            | Message::UnsplashSourceLoaded(request, _)
+            | Message::UnsplashCollectionPhotos(request, _)
+            | Message::UnsplashPrefetched(request, _)
+            | Message::DownloadProgress(request, _)
+                if request != self.request =>
+            {
+                debug!("dropping stale background response {request}");
+                Task::none()
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::LocalColor(request, _) if request != self.request => {
+                debug!("dropping stale background response {request}");
+                Task::none()
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::LocalColor(_, color) => {
+                let color = color.map(|[r, g, b]| Color::from_rgb8(r, g, b));
+
+                // the image may have been read first
+                if self.image_handle.is_none() {
+                    self.placeholder_color = color;
+                }
+
+                self.image_color = self.image_color.or(color);
+
+                Task::none()
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::LocalColorCached(request, _) if request != self.request => {
+                debug!("dropping stale background response {request}");
+                Task::none()
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::LocalColorCached(_, res) => {
+                match res {
+                    Ok([r, g, b]) => self.image_color = Some(Color::from_rgb8(r, g, b)),
+                    Err(e) => debug!("failed to cache background color: {e}"),
+                }
+
+                Task::none()
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::Blurred(request, _) if request != self.request => {
+                debug!("dropping stale background response {request}");
+                Task::none()
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::Blurred(_, res) => {
+                match res {
+                    Ok(handle) => self.image_handle = Some(handle),
+                    Err(e) => error!("failed to blur background: {e}"),
+                }
+
+                Task::none()
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::LocalBackdrop(request, _) if request != self.request => {
+                debug!("dropping stale background response {request}");
+                Task::none()
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::LocalBackdrop(_, res) => {
+                match res {
+                    Ok(backdrop) => self.backdrop = Some(backdrop),
+                    Err(e) => error!("failed to blur background: {e}"),
+                }
+
+                Task::none()
+            }
+            Message::DownloadProgress(_, progress) => {
+                self.download_progress = Some(progress);
+                Task::none()
+            }
+            Message::BackgroundRead(_, res) => match res {
                 Err(e) => {
                     error!("failed to load image: {e}");
+                    self.download_progress = None;
                     Task::none()
                 }
                 Ok(bytes) => {
-                    self.image_handle = Some(image::Handle::from_bytes(bytes));
-                    Task::none()
+                    self.download_progress = None;
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if self.mode == BackgroundMode::Local {
+                        let cache = LocalColorCache::update(self.background.clone(), bytes.clone());
+                        let request = self.request;
+                        let cache = Task::future(cache).map(move |r| {
+                            Message::LocalColorCached(request, r.map_err(|e| e.to_string()))
+                        });
+
+                        if self.fit != BackgroundFit::Contain {
+                            let show = self.show_image(bytes);
+                            return Task::batch([cache, show]);
+                        }
+
+                        let image = bytes.clone();
+                        let backdrop = Task::future(async move {
+                            tokio::task::spawn_blocking(move || blurred_backdrop(&image)).await?
+                        })
+                        .map(move |r| {
+                            Message::LocalBackdrop(request, r.map_err(|e| e.to_string()))
+                        });
+
+                        let show = self.show_image(bytes);
+
+                        return Task::batch([cache, backdrop, show]);
+                    }
+
+                    let show = self.show_image(bytes);
+
+                    if !self.mode.is_unsplash() {
+                        return show;
+                    }
+
+                    Task::batch([show, self.unsplash_displayed()])
                 }
             },
-            Message::UnsplashCollection(res) => match *res {
+            Message::UnsplashPrefetched(_, res) => {
+                match res {
+                    Err(e) => debug!("failed to prefetch photo: {e}"),
+                    Ok(prefetched) => {
+                        if let Some(state) = &mut self.unsplash_state
+                            && prefetched.index == state.step(1)
+                            && prefetched.size == self.size
+                        {
+                            state.prefetched = Some(*prefetched);
+                        }
+                    }
+                }
+
+                Task::none()
+            }
+            Message::UnsplashDownloadTracked(res) => {
+                if let Err(e) = res {
+                    error!("failed to track download: {e}");
+                }
+
+                Task::none()
+            }
+            Message::UnsplashSourceLoaded(_, res) => match res {
                 Err(e) => {
-                    error!("failed to fetch collection: {e}");
+                    error!("failed to fetch {}: {e}", self.mode);
                     Task::none()
                 }
-                Ok(collection) => {
+                Ok((source, 0)) if !self.using_fallback => {
+                    warn!(
+                        "Unsplash {} {} is empty or private, falling back to the {FALLBACK_TOPIC} topic",
+                        source.kind(),
+                        source.id()
+                    );
+
+                    self.using_fallback = true;
+                    self.show_status(format!(
+                        "This Unsplash {} is empty or private, showing the {FALLBACK_TOPIC} topic instead",
+                        source.kind()
+                    ));
+
+                    self.load_unsplash_source(UnsplashSource::Topic(FALLBACK_TOPIC.to_string()))
+                }
+                Ok((source, 0)) => {
+                    error!(
+                        "fallback Unsplash {} {} is empty",
+                        source.kind(),
+                        source.id()
+                    );
+
+                    // nothing to show, leave the background solid
+                    self.image_handle = None;
+                    #[cfg(not(target_arch = "wasm32"))]
+                    {
+                        self.image_bytes = None;
+                    }
+                    self.placeholder_color = None;
+                    self.image_color = None;
+                    self.notice = Some("Couldn't find any Unsplash photos to show".to_string());
+
+                    Task::none()
+                }
+                Ok((source, total)) => {
                     self.unsplash_state = Some(UnsplashState {
-                        collection: collection.id,
+                        source,
                         current: 0,
-                        total: collection.total_photos,
+                        total,
                         paused: false,
 
                         current_page: 0,
                         current_page_photos: None,
+
+                        prefetched: None,
                     });
 
                     Task::done(Message::RequestUnsplash(0))
                 }
             },
             Message::RequestUnsplash(direction) => {
+                if self.unsplash_state.as_ref().is_some_and(|s| s.paused) {
+                    return Task::none();
+                }
+
+                let request = self.next_request();
+
                 match (&self.unsplash_client, &mut self.unsplash_state) {
                     (Some(client), Some(state)) => {
-                        if state.paused {
-                            return Task::none();
-                        }
+                        state.current = state.step(direction);
 
-                        let mut new = state.current as isize + direction;
+                        if let Some(prefetched) = state.prefetched.take()
+                            && prefetched.index == state.current
+                            && prefetched.size == self.size
+                        {
+                            state.current_page = page_position(state.current, UNSPLASH_PER_PAGE).0;
+                            state.current_page_photos = Some(prefetched.page_photos);
+                            let photo = state.current_photo();
+                            self.placeholder_color = photo
+                                .and_then(Photo::average_color)
+                                .map(|[r, g, b]| Color::from_rgb8(r, g, b));
+                            self.image_color = self.placeholder_color;
+                            self.backdrop = photo.and_then(blur_placeholder);
+                            self.image_handle = Some(prefetched.handle);
+                            #[cfg(not(target_arch = "wasm32"))]
+                            {
+                                self.image_bytes = prefetched.bytes;
+                            }
 
-                        if new < 0 {
-                            new = state.total as isize;
-                        } else if new > state.total as isize {
-                            new = 0;
+                            return self.unsplash_displayed();
                         }
 
-                        state.current = new as usize;
-
-                        let page = (state.current / 10) + 1;
+                        let (page, _) = page_position(state.current, UNSPLASH_PER_PAGE);
 
-                        if page == state.current_page && state.current_page_photos.is_some() {
-                            return Task::done(Message::UnsplashCollectionPhotos(Ok(state
-                                .current_page_photos
-                                .as_ref()
-                                .unwrap()
-                                .clone())));
+                        if page == state.current_page
+                            && let Some(photos) = &state.current_page_photos
+                        {
+                            return Task::done(Message::UnsplashCollectionPhotos(
+                                request,
+                                Ok(photos.clone()),
+                            ));
                         }
 
-                        let collection = state.collection.clone();
+                        let source = state.source.clone();
                         let client = client.clone();
+                        let order = self.order;
 
-                        Task::future(async move {
-                            client
-                                .collection_photos(
-                                    &collection,
-                                    Some(CollectionPhotosOptions {
-                                        page: Some(page),
-                                        per_page: Some(10),
-                                        ..Default::default()
-                                    }),
+                        Task::future(async move { source.page(&client, page, order).await }).map(
+                            move |r| {
+                                Message::UnsplashCollectionPhotos(
+                                    request,
+                                    r.map_err(|e| e.to_string()),
                                 )
-                                .await
-                        })
-                        .map(|r| Message::UnsplashCollectionPhotos(r.map_err(|e| e.to_string())))
+                            },
+                        )
                     }
                     _ => Task::none(),
                 }
             }
-            Message::UnsplashCollectionPhotos(res) => match res {
+            Message::UnsplashCollectionPhotos(request, res) => match res {
                 Err(e) => {
                     error!("failed to fetch collection photos: {e}");
                     Task::none()
                 }
                 Ok(photos) => match (&self.unsplash_client, &mut self.unsplash_state) {
                     (Some(client), Some(state)) => {
+                        if let Some(rate_limit) = client.last_rate_limit() {
+                            debug!(
+                                "unsplash rate limit: {}/{} remaining",
+                                rate_limit.remaining, rate_limit.limit
+                            );
+                        }
+
                         state.current_page_photos = Some(photos.clone());
-                        state.current_page = (state.current / 10) + 1;
+                        let (page, idx) = page_position(state.current, UNSPLASH_PER_PAGE);
+                        state.current_page = page;
 
-                        let idx = state.current % 10;
                         let photo = match photos.photos.get(idx) {
                             Some(photo) => photo,
                             None => {
@@ -215,25 +1137,24 @@ impl BackgroundHandle {
                             }
                         };
 
-                        let client = client.clone();
-                        let photo = photo.clone();
-                        let size = self.size;
-
-                        Task::future(async move {
-                            client
-                                .download_photo(
-                                    &photo,
-                                    Some(PhotoFetchOptions {
-                                        fm: Some(Format::Png),
-                                        w: Some(size.width.round().into()),
-                                        h: Some(size.height.round().into()),
-                                        ..Default::default()
-                                    }),
-                                )
-                                .await
-                                .map(|b| b.to_vec())
+                        self.placeholder_color = photo
+                            .average_color()
+                            .map(|[r, g, b]| Color::from_rgb8(r, g, b));
+                        self.image_color = self.placeholder_color;
+
+                        // show the blurred preview while the full image downloads,
+                        // falling back to the average color
+                        self.backdrop = blur_placeholder(photo);
+                        match &self.backdrop {
+                            Some(blurred) => self.image_handle = Some(blurred.clone()),
+                            None if self.placeholder_color.is_some() => self.image_handle = None,
+                            None => {}
+                        }
+
+                        self.download_current().map(move |d| match d {
+                            Download::Progress(p) => Message::DownloadProgress(request, p),
+                            Download::Done(r) => Message::BackgroundRead(request, r),
                         })
-                        .map(|r| Message::BackgroundRead(r.map_err(|e| e.to_string())))
                     }
                     _ => Task::none(),
                 },
@@ -246,10 +1167,123 @@ impl BackgroundHandle {
                     Task::none()
                 }
             }
+            Message::ResizeTick(now) => match self.pending_resize {
+                Some((size, at)) if now.duration_since(at) >= RESIZE_DEBOUNCE => {
+                    self.pending_resize = None;
+
+                    if size == self.size {
+                        return Task::none();
+                    }
+
+                    self.set_size(size);
+
+                    // other modes don't depend on the window size
+                    match (&self.unsplash_state, self.mode) {
+                        (Some(state), mode) if mode.is_unsplash() => {
+                            let current = state.current;
+                            let request = self.request;
+
+                            self.download_current().map(move |d| match d {
+                                Download::Progress(p) => Message::DownloadProgress(request, p),
+                                Download::Done(r) => Message::UnsplashResized(current, r),
+                            })
+                        }
+                        _ => Task::none(),
+                    }
+                }
+                _ => Task::none(),
+            },
+            Message::UnsplashResized(index, res) => {
+                self.download_progress = None;
+
+                // same photo, so no need to track the download again
+                match res {
+                    Err(e) => error!("failed to refetch photo: {e}"),
+                    Ok(bytes) => {
+                        if self.unsplash_state.as_ref().map(|s| s.current) == Some(index) {
+                            return self.show_image(bytes);
+                        }
+                    }
+                }
+
+                Task::none()
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::SaveWallpaper => {
+                let (Some(client), Some(photo)) = (
+                    &self.unsplash_client,
+                    self.unsplash_state.as_ref().and_then(|s| s.current_photo()),
+                ) else {
+                    return Task::none();
+                };
+
+                let client = client.clone();
+                let photo = photo.clone();
+
+                Task::future(async move {
+                    let Some(file) = AsyncFileDialog::new()
+                        .add_filter("image", &["jpg", "jpeg"])
+                        .set_file_name(format!("{}.jpg", photo.slug))
+                        .save_file()
+                        .await
+                    else {
+                        return anyhow::Ok(false);
+                    };
+
+                    // full resolution rather than what fits the window
+                    let bytes = client.download_photo(&photo, None).await?;
+                    tokio::fs::write(file.path(), bytes).await?;
+
+                    Ok(true)
+                })
+                .map(|r| Message::WallpaperSaved(r.map_err(|e| e.to_string())))
+            }
+            #[cfg(target_arch = "wasm32")]
+            Message::SaveWallpaper => {
+                let Some(photo) = self.unsplash_state.as_ref().and_then(|s| s.current_photo())
+                else {
+                    return Task::none();
+                };
+
+                // Unsplash serves the photo as an attachment when forced
+                let download = &photo.links.download;
+                let separator = if download.contains('?') { '&' } else { '?' };
+                let url = format!("{download}{separator}force=true");
+
+                self.show_status("Download started");
+                Task::done(Message::OpenUrl(url))
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::WallpaperSaved(res) => {
+                match res {
+                    Err(e) => {
+                        error!("failed to save wallpaper: {e}");
+                        self.show_status(format!("Failed to save wallpaper: {e}"));
+                    }
+                    Ok(true) => self.show_status("Wallpaper saved"),
+                    // cancelled
+                    Ok(false) => {}
+                }
+
+                Task::none()
+            }
+            Message::StatusTick(now) => {
+                if self.status.as_ref().is_some_and(|(_, until)| now >= *until) {
+                    self.status = None;
+                }
+
+                Task::none()
+            }
+            Message::OpenPhotoPage => self.open_attribution(|a| a.photo_url),
+            Message::OpenAuthorProfile => self.open_attribution(|a| a.author_url),
             #[cfg(not(target_arch = "wasm32"))]
             Message::OpenUrl(url) => {
-                if let Err(e) = open::that_detached(url) {
-                    error!("failed to open link: {e}")
+                if let Err(e) = open::that_detached(&url) {
+                    error!("failed to open link: {e}");
+
+                    // no browser to open it in, let the user paste it somewhere
+                    self.show_status("Couldn't open a browser, link copied");
+                    return clipboard::write(url);
                 }
 
                 Task::none()
@@ -275,81 +1309,180 @@ impl BackgroundHandle {
             .into()
     }
 
-    pub fn view(&self) -> Element<Message> {
+    fn gradient<'a>(gradient: Gradient) -> Element<'a, Message> {
+        container("")
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(move |_| container::background(gradient))
+            .into()
+    }
+
+    /// Darken `background` by the configured overlay opacity.
+    fn dimmed<'a>(&self, background: impl Into<Element<'a, Message>>) -> Element<'a, Message> {
+        if self.overlay_opacity <= 0.0 {
+            return background.into();
+        }
+
+        stack![
+            background.into(),
+            Self::solid(Color::BLACK.scale_alpha(self.overlay_opacity))
+        ]
+        .into()
+    }
+
+    /// Description, location and likes of `photo`, skipping missing ones.
+    fn photo_info<'a>(&self, photo: &'a Photo) -> Option<Element<'a, Message>> {
+        let lines: Vec<String> = [
+            photo
+                .description
+                .clone()
+                .or_else(|| photo.alt_description.clone()),
+            photo.user.location.clone(),
+            (photo.likes > 0).then(|| format!("{} likes", photo.likes)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        if lines.is_empty() {
+            return None;
+        }
+
+        let info = column(
+            lines
+                .into_iter()
+                .map(|line| text(line).color(Color::WHITE).into()),
+        )
+        .spacing(5)
+        .max_width(400);
+
+        Some(
+            container(info)
+                .padding(10)
+                .style(|_| container::background(Color::BLACK.scale_alpha(0.5)))
+                .into(),
+        )
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let mut background = stack![self.view_background()];
+
+        if let Some(progress) = self.download_progress {
+            background = background.push(
+                progress_bar(0.0..=1.0, progress)
+                    .height(Length::Fixed(3.0))
+                    .style(progress_bar::primary),
+            );
+        }
+
+        if let Some(notice) = &self.notice {
+            background = background.push(
+                container(
+                    container(text(notice).color(Color::WHITE))
+                        .padding(10)
+                        .style(|_| container::background(Color::BLACK.scale_alpha(0.5))),
+                )
+                // the clock takes the center
+                .center_x(Length::Fill)
+                .align_bottom(Length::Fill)
+                .padding(15),
+            );
+        }
+
+        if let Some((status, _)) = &self.status {
+            background = background.push(
+                container(
+                    container(text(status).color(Color::WHITE))
+                        .padding(10)
+                        .style(|_| container::background(Color::BLACK.scale_alpha(0.5))),
+                )
+                .align_right(Length::Fill)
+                .align_bottom(Length::Fill)
+                .padding(15),
+            );
+        }
+
+        background.into()
+    }
+
+    fn view_background(&self) -> Element<'_, Message> {
         match self.mode {
             BackgroundMode::Solid => {
                 Self::solid(Color::parse(&self.background).unwrap_or(Color::BLACK))
             }
+            BackgroundMode::Gradient => match parse_gradient(&self.background) {
+                Some(gradient) => self.dimmed(Self::gradient(gradient)),
+                None => Self::solid(Color::BLACK),
+            },
             _ => {
                 if let Some(handle) = &self.image_handle {
-                    let img = image(handle)
-                        .content_fit(ContentFit::Cover)
+                    let photo = image(handle)
+                        .content_fit(self.fit.content_fit())
                         .width(Length::Fill)
                         .height(Length::Fill);
 
+                    let img = match (&self.backdrop, self.fit) {
+                        (Some(backdrop), BackgroundFit::Contain) => self.dimmed(stack![
+                            image(backdrop)
+                                .content_fit(ContentFit::Cover)
+                                .width(Length::Fill)
+                                .height(Length::Fill),
+                            photo
+                        ]),
+                        _ => self.dimmed(photo),
+                    };
+
                     #[cfg(not(target_arch = "wasm32"))]
                     if self.mode == BackgroundMode::Local {
-                        return img.into();
+                        return img;
                     }
 
                     if let Some(state) = &self.unsplash_state {
-                        let idx = state.current % 10;
-                        if let Some(photo) = state
-                            .current_page_photos
-                            .as_ref()
-                            .and_then(|c| c.photos.get(idx))
-                        {
-                            let suffix = "?utm_source=fjordgard&utm_medium=referral";
+                        if let Some(photo) = state.current_photo() {
+                            let attribution = photo.attribution();
+                            let photo_url = attribution.photo_url;
+                            let author_url = attribution.author_url;
+                            let unsplash_url = attribution.unsplash_url;
 
-                            let photo_url = format!("{}{suffix}", photo.links.html);
+                            let attribution = row![
+                                button(text("Photo").color(self.text_color()))
+                                    .style(button::text)
+                                    .on_press_with(move || Message::OpenUrl(photo_url.clone())),
+                                text(".").color(self.text_color()),
+                                button(text(attribution.author_name).color(self.text_color()))
+                                    .style(button::text)
+                                    .on_press_with(move || Message::OpenUrl(author_url.clone())),
+                                text(".").color(self.text_color()),
+                                button(text("Unsplash").color(self.text_color()))
+                                    .style(button::text)
+                                    .on_press_with(move || Message::OpenUrl(unsplash_url.clone())),
+                            ]
+                            .spacing(0);
 
-                            let user = &photo.user;
+                            let mut credits = column![];
 
-                            let author = format!(
-                                "{}{}",
-                                user.first_name,
-                                user.last_name
-                                    .as_ref()
-                                    .map(|l| format!(" {l}"))
-                                    .unwrap_or_default()
-                            );
-                            let author_url = format!("{}{suffix}", user.links.html);
+                            if self.show_photo_info
+                                && let Some(info) = self.photo_info(photo)
+                            {
+                                credits = credits.push(info);
+                            }
 
                             stack![
-                                img,
-                                container(
-                                    row![
-                                        button(text("Photo").color(Color::WHITE))
-                                            .style(button::text)
-                                            .on_press_with(move || Message::OpenUrl(
-                                                photo_url.clone()
-                                            )),
-                                        text(".").color(Color::WHITE),
-                                        button(text(author).color(Color::WHITE))
-                                            .style(button::text)
-                                            .on_press_with(move || Message::OpenUrl(
-                                                author_url.clone()
-                                            )),
-                                        text(".").color(Color::WHITE),
-                                        button(text("Unsplash").color(Color::WHITE))
-                                            .style(button::text)
-                                            .on_press_with(move || Message::OpenUrl(format!(
-                                                "https://unsplash.com/{suffix}"
-                                            ))),
-                                    ]
-                                    .spacing(0)
-                                )
-                                .align_left(Length::Fill)
-                                .align_bottom(Length::Fill)
-                                .padding(15)
+                                mouse_area(img).on_middle_press(Message::OpenPhotoPage),
+                                container(credits.push(attribution))
+                                    .align_left(Length::Fill)
+                                    .align_bottom(Length::Fill)
+                                    .padding(15)
                             ]
                             .into()
                         } else {
-                            img.into()
+                            img
                         }
                     } else {
-                        img.into()
+                        img
                     }
+                } else if let Some(color) = self.placeholder_color {
+                    self.dimmed(Self::solid(color))
                 } else {
                     Self::solid(Color::BLACK)
                 }
@@ -357,3 +1490,149 @@ impl BackgroundHandle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(current: usize, total: usize) -> UnsplashState {
+        UnsplashState {
+            source: UnsplashSource::Collection("1053828".to_string()),
+            current,
+            total,
+            paused: false,
+
+            current_page_photos: None,
+            current_page: 0,
+
+            prefetched: None,
+        }
+    }
+
+    #[test]
+    fn page_positions() {
+        assert_eq!(page_position(0, 7), (1, 0));
+        assert_eq!(page_position(6, 7), (1, 6));
+        assert_eq!(page_position(7, 7), (2, 0));
+        assert_eq!(page_position(23, 7), (4, 2));
+
+        for index in 0..50 {
+            let (page, offset) = page_position(index, 7);
+            assert_eq!((page - 1) * 7 + offset, index);
+        }
+    }
+
+    #[test]
+    fn data_saver_options() {
+        let full = BackgroundHandle::photo_options(Size::new(3840.0, 2160.0), false);
+        assert_eq!((full.w, full.h), (Some(3840.0), Some(2160.0)));
+        assert_eq!(full.q, None);
+
+        let saver = BackgroundHandle::photo_options(Size::new(3840.0, 2160.0), true);
+        assert_eq!((saver.w, saver.h), (Some(1920.0), Some(1080.0)));
+        assert_eq!(saver.fm, Some(Format::Jpg));
+        assert_eq!(saver.q, Some(DATA_SAVER_QUALITY));
+
+        // smaller windows aren't scaled up
+        let small = BackgroundHandle::photo_options(Size::new(800.0, 600.0), true);
+        assert_eq!((small.w, small.h), (Some(800.0), Some(600.0)));
+    }
+
+    #[test]
+    fn step_wraps_forward() {
+        assert_eq!(state(0, 25).step(1), 1);
+        assert_eq!(state(23, 25).step(1), 24);
+        assert_eq!(state(24, 25).step(1), 0);
+    }
+
+    #[test]
+    fn step_wraps_backward() {
+        assert_eq!(state(1, 25).step(-1), 0);
+        assert_eq!(state(0, 25).step(-1), 24);
+    }
+
+    #[test]
+    fn step_single_photo() {
+        assert_eq!(state(0, 1).step(1), 0);
+        assert_eq!(state(0, 1).step(-1), 0);
+        assert_eq!(state(0, 1).step(0), 0);
+    }
+
+    #[test]
+    fn contrasting_text_colors() {
+        assert_eq!(contrasting_text(Color::WHITE), Color::BLACK);
+        assert_eq!(
+            contrasting_text(Color::from_rgb8(0xf0, 0xd0, 0x60)),
+            Color::BLACK
+        );
+        assert_eq!(contrasting_text(Color::BLACK), Color::WHITE);
+        assert_eq!(
+            contrasting_text(Color::from_rgb8(0x20, 0x20, 0x40)),
+            Color::WHITE
+        );
+        // mid grey is darker than it looks once linearized
+        assert_eq!(
+            contrasting_text(Color::from_rgb8(0x80, 0x80, 0x80)),
+            Color::BLACK
+        );
+    }
+
+    #[test]
+    fn gradient_text_color() {
+        let mut config = Config {
+            background_mode: BackgroundMode::Gradient,
+            background: "90deg,#ffffff,#e0e0e0".to_string(),
+            ..Default::default()
+        };
+        let (handle, _) = BackgroundHandle::new(&config, Size::new(800.0, 600.0));
+        assert_eq!(handle.text_color(), Color::BLACK);
+
+        config.overlay_opacity = 0.8;
+        let (handle, _) = BackgroundHandle::new(&config, Size::new(800.0, 600.0));
+        assert_eq!(handle.text_color(), Color::WHITE);
+
+        config.text_color = Some("#ff0000".to_string());
+        let (handle, _) = BackgroundHandle::new(&config, Size::new(800.0, 600.0));
+        assert_eq!(handle.text_color(), Color::from_rgb8(0xff, 0, 0));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn large_blur_scales_down() {
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        ::image::RgbImage::new(100, 60)
+            .write_to(&mut bytes, ::image::ImageOutputFormat::Png)
+            .unwrap();
+        let bytes = bytes.into_inner();
+
+        let size = |handle| match handle {
+            image::Handle::Rgba { width, height, .. } => (width, height),
+            _ => panic!("expected decoded pixels"),
+        };
+
+        assert_eq!(size(blurred_image(&bytes, 2.0).unwrap()), (100, 60));
+        assert_eq!(size(blurred_image(&bytes, 16.0).unwrap()), (25, 15));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn mock_source_loads() {
+        let client = UnsplashClient::builder().mock(true).build().unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        let (source, total) = runtime
+            .block_on(UnsplashSource::Collection("any".to_string()).load(&client))
+            .unwrap();
+        assert_eq!(total, 3);
+
+        let bytes = runtime
+            .block_on(async {
+                let page = source.page(&client, 1, PhotoOrder::default()).await?;
+                client.download_photo(&page.photos[0], None).await
+            })
+            .unwrap();
+        assert!(blurred_backdrop(&bytes).is_ok());
+    }
+}
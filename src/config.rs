@@ -1,13 +1,64 @@
 #[cfg(not(target_arch = "wasm32"))]
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::Duration,
+};
+
+use chrono::format::{Item, StrftimeItems};
+#[cfg(not(target_arch = "wasm32"))]
 use directories::ProjectDirs;
-use serde::{Deserialize, Serialize};
+use fjordgard_unsplash::model::PhotoOrderBy;
+use fjordgard_weather::model::{PrecipitationUnit, SpeedUnit, TemperatureUnit, WeatherModel};
+#[cfg(not(target_arch = "wasm32"))]
+use iced::futures::{SinkExt, Stream, StreamExt, channel::mpsc};
+use iced::{Color, ContentFit, Point, Size, Theme};
+#[cfg(not(target_arch = "wasm32"))]
+use log::{error, warn};
+use serde::{Deserialize, Deserializer, Serialize};
+
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Default,
+    strum::Display,
+    strum::VariantArray,
+)]
+pub enum AppTheme {
+    Light,
+    Dark,
+    /// Follow the desktop, always dark on wasm.
+    #[default]
+    System,
+}
+
+impl AppTheme {
+    pub fn theme(&self) -> Theme {
+        match self {
+            Self::Light => Theme::Light,
+            Self::Dark => Theme::Dark,
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::System => Theme::default(),
+            #[cfg(target_arch = "wasm32")]
+            Self::System => Theme::Dark,
+        }
+    }
+}
 
 #[derive(
     Serialize, Deserialize, Debug, Clone, Copy, PartialEq, strum::Display, strum::VariantArray,
 )]
 pub enum BackgroundMode {
+    #[strum(to_string = "Unsplash collection")]
     Unsplash,
+    #[strum(to_string = "Unsplash topic")]
+    UnsplashTopic,
     Solid,
+    Gradient,
     #[cfg(not(target_arch = "wasm32"))]
     Local,
 }
@@ -17,70 +68,599 @@ impl BackgroundMode {
         match self {
             // https://unsplash.com/collections/1053828/tabliss-official
             Self::Unsplash => "1053828",
+            Self::UnsplashTopic => "wallpapers",
             Self::Solid => "#000000",
+            Self::Gradient => "180deg,#000000,#202040",
             #[cfg(not(target_arch = "wasm32"))]
             Self::Local => "",
         }
     }
 
+    pub fn is_unsplash(&self) -> bool {
+        matches!(self, Self::Unsplash | Self::UnsplashTopic)
+    }
+
     pub fn edit_text(&self) -> &'static str {
         match self {
             Self::Unsplash => "Unsplash collection",
-            Self::Solid => "Color (#rrggbb)",
+            Self::UnsplashTopic => "Unsplash topic",
+            Self::Solid => "Color (#rrggbb[aa])",
+            Self::Gradient => "Gradient (angle,#rrggbb,#rrggbb...)",
             #[cfg(not(target_arch = "wasm32"))]
             Self::Local => "File path",
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+/// How image backgrounds are scaled to the window.
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Default,
+    strum::Display,
+    strum::VariantArray,
+)]
+pub enum BackgroundFit {
+    /// Fill the window, cropping the image.
+    #[default]
+    Cover,
+    /// Show the whole image, over a blurred copy filling the bars.
+    Contain,
+    /// Stretch the image to the window.
+    Fill,
+}
+
+impl BackgroundFit {
+    pub fn content_fit(&self) -> ContentFit {
+        match self {
+            Self::Cover => ContentFit::Cover,
+            Self::Contain => ContentFit::Contain,
+            Self::Fill => ContentFit::Fill,
+        }
+    }
+}
+
+/// Order Unsplash photos are rotated through.
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Default,
+    strum::Display,
+    strum::VariantArray,
+)]
+pub enum PhotoOrder {
+    /// Unsplash's own order, newest first.
+    #[default]
+    Latest,
+    Oldest,
+    #[strum(to_string = "Popular first")]
+    Popular,
+}
+
+impl PhotoOrder {
+    /// `None` leaves the order to Unsplash.
+    pub fn order_by(&self) -> Option<PhotoOrderBy> {
+        match self {
+            Self::Latest => None,
+            Self::Oldest => Some(PhotoOrderBy::Oldest),
+            Self::Popular => Some(PhotoOrderBy::Popular),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub enum TimePreset {
+    #[default]
+    TwelveHour,
+    TwentyFourHour,
+    /// A strftime format string.
+    Custom(String),
+}
+
+impl TimePreset {
+    pub fn format(&self) -> &str {
+        match self {
+            Self::TwelveHour => "%-I:%M:%S",
+            Self::TwentyFourHour => "%H:%M:%S",
+            Self::Custom(format) => format,
+        }
+    }
+
+    /// Hours and minutes only, for times other than the clock's. Custom
+    /// formats are 12-hour if they show an AM/PM hour.
+    pub fn short_format(&self) -> &'static str {
+        const TWELVE_HOUR: [&str; 5] = ["%I", "%-I", "%l", "%r", "%p"];
+
+        match self {
+            Self::TwelveHour => "%-I:%M %p",
+            Self::TwentyFourHour => "%H:%M",
+            Self::Custom(format) if TWELVE_HOUR.iter().any(|s| format.contains(s)) => "%-I:%M %p",
+            Self::Custom(_) => "%H:%M",
+        }
+    }
+}
+
+/// Parse a strftime format string, `None` if it contains unknown specifiers.
+pub fn parse_time_format(format: &str) -> Option<Vec<Item<'static>>> {
+    StrftimeItems::new(format).parse_to_owned().ok()
+}
+
+/// The Unsplash collection id in `s`, which is either the id itself or a
+/// collection URL like `https://unsplash.com/collections/1053828/tabliss-official`.
+/// Ids are numeric, or 11 URL-safe characters for newer collections.
+pub fn parse_collection_id(s: &str) -> Option<&str> {
+    let s = s.trim();
+    let id = match s.split_once("unsplash.com/collections/") {
+        Some((_, rest)) => rest.split(['/', '?', '#']).next()?,
+        None => s,
+    };
+
+    let numeric = !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit());
+    let slug = id.len() == 11
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+
+    (numeric || slug).then_some(id)
+}
+
+/// Smallest restored window, so a bad config can't hide the window.
+const MIN_WINDOW_SIZE: Size = Size::new(200.0, 150.0);
+/// Largest restored window size and position, larger than any display.
+const MAX_WINDOW_EXTENT: f32 = 16384.0;
+/// Largest [`Config::background_blur`].
+pub const MAX_BACKGROUND_BLUR: f32 = 40.0;
+
+/// Size and position of the main window when it last settled.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct WindowGeometry {
+    pub width: f32,
+    pub height: f32,
+    /// Unset on platforms that don't report window positions.
+    pub position: Option<(f32, f32)>,
+}
+
+impl WindowGeometry {
+    /// The saved size, clamped to something that fits on a display.
+    pub fn size(&self) -> Size {
+        let clamp = |v: f32, min: f32| {
+            if v.is_finite() {
+                v.clamp(min, MAX_WINDOW_EXTENT)
+            } else {
+                min
+            }
+        };
+
+        Size::new(
+            clamp(self.width, MIN_WINDOW_SIZE.width),
+            clamp(self.height, MIN_WINDOW_SIZE.height),
+        )
+    }
+
+    /// The saved position, `None` if it would put the window off screen.
+    ///
+    /// iced doesn't expose monitor bounds, so positions left or above the
+    /// primary display, or past any realistic display, are dropped and the
+    /// window is placed by the system instead.
+    pub fn position(&self) -> Option<Point> {
+        let (x, y) = self.position?;
+        let on_screen = |v: f32| v.is_finite() && (0.0..MAX_WINDOW_EXTENT).contains(&v);
+
+        (on_screen(x) && on_screen(y)).then_some(Point::new(x, y))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Location {
     pub longitude: f64,
     pub latitude: f64,
     pub name: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Config {
-    pub time_format: String,
+    /// Schema version, see [`Config::migrate`].
+    #[serde(default)]
+    pub version: u32,
+    pub time_format: TimePreset,
     pub background_mode: BackgroundMode,
     pub background: String,
     pub unsplash_key: Option<String>,
     pub location: Option<Location>,
+    /// Request a transparent main window so the desktop shows through a
+    /// translucent solid background. Depends on compositor support and only
+    /// applies on desktop, takes effect on restart.
+    #[serde(default)]
+    pub transparent_window: bool,
+    /// Open the main window fullscreen, for dedicated displays. Desktop only,
+    /// F11 toggles it at runtime.
+    #[serde(default)]
+    pub start_fullscreen: bool,
+    /// Restored when the main window opens. Desktop only.
+    #[serde(default)]
+    pub window: Option<WindowGeometry>,
+    /// Opacity of the black layer drawn over image and gradient backgrounds,
+    /// between `0.0` and `1.0`.
+    #[serde(default, deserialize_with = "opacity")]
+    pub overlay_opacity: f32,
+    /// Radius in pixels photo and local image backgrounds are blurred by, up
+    /// to [`MAX_BACKGROUND_BLUR`]. Solid colors and gradients are smooth
+    /// already. Desktop only.
+    #[serde(default, deserialize_with = "background_blur")]
+    pub background_blur: f32,
+    /// IANA time zone names shown as smaller clocks under the main one.
+    #[serde(default)]
+    pub extra_timezones: Vec<String>,
+    #[serde(default)]
+    pub theme: AppTheme,
+    /// Color of text and icons drawn over the background. If unset, black or
+    /// white is picked to stand out against the background.
+    #[serde(default)]
+    pub text_color: Option<String>,
+    /// Installed font family used for the clock, the default font if unset or
+    /// not found.
+    #[serde(default)]
+    pub font_family: Option<String>,
+    #[serde(default = "default_clock_size")]
+    pub clock_size: u16,
+    /// Seconds between automatic Unsplash wallpaper changes, `0` to never
+    /// rotate.
+    #[serde(default = "default_interval")]
+    pub rotation_interval_secs: u64,
+    /// Seconds between forecast refreshes, `0` to only fetch on startup and
+    /// location changes.
+    #[serde(default = "default_interval")]
+    pub forecast_interval_secs: u64,
+    /// Forecast model to use, Open-Meteo picks one if unset.
+    #[serde(default)]
+    pub weather_model: Option<WeatherModel>,
+    /// Show the description, location and likes of Unsplash photos.
+    #[serde(default)]
+    pub show_photo_info: bool,
+    #[serde(default)]
+    pub background_fit: BackgroundFit,
+    /// Download smaller, compressed Unsplash photos for metered connections.
+    #[serde(default)]
+    pub data_saver: bool,
+    /// Pixel density Unsplash photos are downloaded at, the display's if
+    /// unset. Between `1.0` and `3.0`.
+    #[serde(default)]
+    pub photo_scale: Option<f32>,
+    #[serde(default)]
+    pub photo_order: PhotoOrder,
+    #[serde(default)]
+    pub temperature_unit: TemperatureUnit,
+    #[serde(default)]
+    pub wind_unit: SpeedUnit,
+    #[serde(default)]
+    pub precipitation_unit: PrecipitationUnit,
+    /// Serve bundled Unsplash and Open-Meteo responses instead of using the
+    /// network, set by `--mock` or `FJORDGARD_MOCK`. Never saved.
+    #[serde(skip)]
+    pub mock: bool,
+    /// The saved config couldn't be loaded, so defaults are used and it isn't
+    /// overwritten. Never saved.
+    #[serde(skip)]
+    pub read_only: bool,
+}
+
+fn default_clock_size() -> u16 {
+    200
+}
+
+fn default_interval() -> u64 {
+    60 * 15
+}
+
+fn background_blur<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    let blur = f32::deserialize(deserializer)?;
+
+    Ok(if blur.is_nan() {
+        0.0
+    } else {
+        blur.clamp(0.0, MAX_BACKGROUND_BLUR)
+    })
+}
+
+/// Replace `path` with `contents`, writing them to disk before renaming a
+/// temporary file into place so `path` is never left empty or half written.
+#[cfg(not(target_arch = "wasm32"))]
+fn write_atomic(path: &Path, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let temp = with_suffix(path, ".tmp");
+
+    let mut file = std::fs::File::create(&temp)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+
+    std::fs::rename(temp, path)
+}
+
+/// Whether files can be created in `dir`, checked by creating one.
+#[cfg(not(target_arch = "wasm32"))]
+fn writable(dir: &Path) -> bool {
+    let probe = dir.join(".fjordgard-probe");
+    let created = std::fs::File::create(&probe).is_ok();
+
+    if created {
+        let _ = std::fs::remove_file(probe);
+    }
+
+    created
+}
+
+/// `path` with `suffix` appended to its file name.
+#[cfg(not(target_arch = "wasm32"))]
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+
+    path.into()
+}
+
+fn opacity<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    let opacity = f32::deserialize(deserializer)?;
+
+    Ok(if opacity.is_nan() {
+        0.0
+    } else {
+        opacity.clamp(0.0, 1.0)
+    })
 }
 
 impl Config {
+    /// The fixed text color, if one is set and valid.
+    pub fn text_color(&self) -> Option<Color> {
+        self.text_color.as_deref().and_then(Color::parse)
+    }
+
+    /// Units forecasts are requested in, changing any needs a new forecast.
+    pub fn weather_units(&self) -> (TemperatureUnit, SpeedUnit, PrecipitationUnit) {
+        (
+            self.temperature_unit,
+            self.wind_unit,
+            self.precipitation_unit,
+        )
+    }
+}
+
+/// Bumped whenever the saved shape of [`Config`] changes.
+pub const CONFIG_VERSION: u32 = 1;
+
+impl Config {
+    /// Upgrade a saved config from any older version to [`CONFIG_VERSION`].
+    pub fn migrate(mut value: serde_json::Value) -> anyhow::Result<Config> {
+        let object = value
+            .as_object_mut()
+            .ok_or_else(|| anyhow::anyhow!("expected config to be an object"))?;
+
+        let version = object
+            .get("version")
+            .and_then(|v| v.as_u64())
+            .unwrap_or_default();
+
+        if version > CONFIG_VERSION.into() {
+            anyhow::bail!("config version {version} is newer than supported");
+        }
+
+        // v0 stored `time_format` as a bare strftime string
+        if version < 1
+            && let Some(time_format) = object.get_mut("time_format")
+            && let serde_json::Value::String(format) = time_format
+        {
+            *time_format = serde_json::json!({ "Custom": format });
+        }
+
+        object.insert("version".to_string(), CONFIG_VERSION.into());
+
+        Ok(serde_json::from_value(value)?)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn project_dirs() -> Option<ProjectDirs> {
+        ProjectDirs::from("gay.gayest", "", "fjordgard")
+    }
+
+    /// The config file in the platform's config directory, if it has one.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn platform_path() -> Option<PathBuf> {
+        Self::project_dirs().map(|d| d.config_dir().join("config.json"))
+    }
+
+    /// Where to cache `name`, in the platform's cache directory or next to
+    /// [`Config::fallback_path`] if it has none.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn cache_path(name: &str) -> PathBuf {
+        match Self::project_dirs() {
+            Some(dirs) => dirs.cache_dir().join(name),
+            None => Self::fallback_path().with_file_name(format!("fjordgard-{name}")),
+        }
+    }
+
+    /// The config file in the platform's config directory, or
+    /// [`Config::fallback_path`] if it has none.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn path() -> PathBuf {
+        Self::platform_path().unwrap_or_else(Self::fallback_path)
+    }
+
+    /// Where the config is kept on systems without a config directory, next
+    /// to the executable if that directory is writable, otherwise in the temp
+    /// directory. Worked out once per run.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn fallback_path() -> PathBuf {
+        static FALLBACK_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+        FALLBACK_PATH
+            .get_or_init(|| {
+                std::env::current_exe()
+                    .ok()
+                    .and_then(|exe| exe.parent().map(Path::to_path_buf))
+                    .filter(|dir| writable(dir))
+                    .map(|dir| dir.join("fjordgard-config.json"))
+                    .unwrap_or_else(|| std::env::temp_dir().join("fjordgard").join("config.json"))
+            })
+            .clone()
+    }
+
+    /// Whether the config lives at [`Config::fallback_path`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn using_fallback_path() -> bool {
+        Self::platform_path().is_none()
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     pub fn load() -> anyhow::Result<Config> {
-        if let Some(dir) = ProjectDirs::from("gay.gayest", "", "fjordgard") {
-            let config_file = dir.config_dir().join("config.json");
+        Self::load_from(&Self::path())
+    }
 
-            if !config_file.exists() {
-                return Ok(Config::default());
-            }
+    /// Read the config at `path`, or the backup written alongside it if the
+    /// file itself can't be read.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_from(path: &Path) -> anyhow::Result<Config> {
+        let read = |path: &Path| -> anyhow::Result<Config> {
+            let data = std::fs::read_to_string(path)?;
 
-            let data = std::fs::read_to_string(config_file)?;
+            Self::migrate(serde_json::from_str(&data)?)
+        };
 
-            Ok(serde_json::from_str(&data)?)
-        } else {
-            Ok(Config::default())
+        if !path.exists() {
+            return Ok(Config::default());
         }
+
+        read(path).or_else(|e| {
+            let backup = with_suffix(path, ".bak");
+
+            if !backup.exists() {
+                return Err(e);
+            }
+
+            warn!("failed to load config, using the backup: {e}");
+            read(&backup).map_err(|_| e)
+        })
     }
 
     #[cfg(not(target_arch = "wasm32"))]
     pub async fn save(&self) -> anyhow::Result<()> {
-        if let Some(dir) = ProjectDirs::from("gay.gayest", "", "fjordgard") {
-            let config_dir = dir.config_dir();
-            tokio::fs::create_dir_all(config_dir).await?;
+        if self.read_only {
+            anyhow::bail!("the saved config couldn't be loaded, not overwriting it");
+        }
+
+        self.save_to(&Self::path()).await
+    }
 
-            let contents = serde_json::to_string(self)?;
+    /// Write the config to `path` and a backup next to it, each through a
+    /// temporary file renamed into place so a crash can't leave either half
+    /// written. Only failing to write `path` itself is an error.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn save_to(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
 
-            tokio::fs::write(config_dir.join("config.json"), contents).await?;
+        let contents = serde_json::to_string(self)?;
 
-            Ok(())
-        } else {
-            anyhow::bail!("no config directory found")
+        let write = |path: PathBuf, contents: String| {
+            tokio::task::spawn_blocking(move || write_atomic(&path, &contents))
+        };
+
+        write(path.to_path_buf(), contents.clone()).await??;
+
+        if let Err(e) = write(with_suffix(path, ".bak"), contents).await? {
+            warn!("failed to back up config: {e}");
         }
+
+        Ok(())
+    }
+
+    /// Write the config to an arbitrary file, for moving between machines.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn export_to(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let contents = serde_json::to_string_pretty(self)?;
+
+        tokio::fs::write(path, contents).await?;
+
+        Ok(())
+    }
+
+    /// Read a config written by [`Config::export_to`], migrating it if needed.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn import_from(path: impl AsRef<Path>) -> anyhow::Result<Config> {
+        let data = tokio::fs::read_to_string(path).await?;
+
+        Self::migrate(serde_json::from_str(&data)?)
+    }
+
+    /// Reload the config whenever it changes on disk.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn watch() -> impl Stream<Item = anyhow::Result<Config>> {
+        iced::stream::channel(10, |mut output| async move {
+            use notify::{EventKind, RecursiveMode, Watcher};
+
+            let path = Self::path();
+            let Some(dir) = path.parent() else {
+                return;
+            };
+
+            let (tx, mut rx) = mpsc::unbounded();
+
+            // editors often replace the file rather than writing to it, so
+            // watch the directory instead
+            let watcher = notify::recommended_watcher(move |res| {
+                let _ = tx.unbounded_send(res);
+            })
+            .and_then(|mut w| {
+                std::fs::create_dir_all(dir)?;
+                w.watch(dir, RecursiveMode::NonRecursive)?;
+                Ok(w)
+            });
+
+            let _watcher = match watcher {
+                Ok(w) => w,
+                Err(e) => {
+                    error!("failed to watch config: {e}");
+                    return;
+                }
+            };
+
+            while let Some(res) = rx.next().await {
+                let event: notify::Event = match res {
+                    Ok(event) => event,
+                    Err(e) => {
+                        error!("config watch error: {e}");
+                        continue;
+                    }
+                };
+
+                if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                    || !event.paths.iter().any(|p| p == &path)
+                {
+                    continue;
+                }
+
+                // a single save usually produces a burst of events
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                while rx.try_next().is_ok_and(|e| e.is_some()) {}
+
+                let config = tokio::fs::read_to_string(&path)
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .and_then(|data| Self::migrate(serde_json::from_str(&data)?));
+
+                if output.send(config).await.is_err() {
+                    return;
+                }
+            }
+        })
     }
 
     #[cfg(target_arch = "wasm32")]
@@ -98,7 +678,7 @@ impl Config {
         let storage = Self::get_storage()?;
 
         if let Some(config) = storage.get_item("config").ok().flatten() {
-            Ok(serde_json::from_str(&config)?)
+            Self::migrate(serde_json::from_str(&config)?)
         } else {
             Ok(Config::default())
         }
@@ -106,6 +686,10 @@ impl Config {
 
     #[cfg(target_arch = "wasm32")]
     pub async fn save(&self) -> anyhow::Result<()> {
+        if self.read_only {
+            anyhow::bail!("the saved config couldn't be loaded, not overwriting it");
+        }
+
         let storage = Self::get_storage()?;
         let config = serde_json::to_string(self)?;
 
@@ -120,11 +704,181 @@ impl Config {
 impl Default for Config {
     fn default() -> Self {
         Self {
-            time_format: String::from("%-I:%M:%S"),
+            version: CONFIG_VERSION,
+            time_format: TimePreset::default(),
             background_mode: BackgroundMode::Solid,
             background: BackgroundMode::Solid.default_background().to_string(),
             unsplash_key: None,
             location: None,
+            transparent_window: false,
+            start_fullscreen: false,
+            window: None,
+            overlay_opacity: 0.0,
+            background_blur: 0.0,
+            extra_timezones: vec![],
+            theme: AppTheme::default(),
+            text_color: None,
+            font_family: None,
+            clock_size: default_clock_size(),
+            rotation_interval_secs: default_interval(),
+            forecast_interval_secs: default_interval(),
+            weather_model: None,
+            show_photo_info: false,
+            background_fit: BackgroundFit::default(),
+            data_saver: false,
+            photo_scale: None,
+            photo_order: PhotoOrder::default(),
+            temperature_unit: TemperatureUnit::default(),
+            wind_unit: SpeedUnit::default(),
+            precipitation_unit: PrecipitationUnit::default(),
+            mock: false,
+            read_only: false,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrate_v0() {
+        let v0 = serde_json::json!({
+            "time_format": "%H:%M",
+            "background_mode": "Unsplash",
+            "background": "1053828",
+            "unsplash_key": "key",
+            "location": {
+                "longitude": -0.12,
+                "latitude": 51.5,
+                "name": "London"
+            }
+        });
+
+        let config = Config::migrate(v0).unwrap();
+
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.time_format, TimePreset::Custom("%H:%M".to_string()));
+        assert_eq!(config.background_mode, BackgroundMode::Unsplash);
+        assert_eq!(config.background, "1053828");
+        assert_eq!(config.unsplash_key.as_deref(), Some("key"));
+
+        let location = config.location.unwrap();
+        assert_eq!(location.latitude, 51.5);
+        assert_eq!(location.longitude, -0.12);
+        assert_eq!(location.name.as_deref(), Some("London"));
+
+        assert!(!config.transparent_window);
+        assert_eq!(config.overlay_opacity, 0.0);
+        assert_eq!(config.background_blur, 0.0);
+        assert!(config.extra_timezones.is_empty());
+    }
+
+    #[test]
+    fn migrate_current() {
+        let config = Config::default();
+        let value = serde_json::to_value(&config).unwrap();
+
+        let migrated = Config::migrate(value).unwrap();
+
+        assert_eq!(migrated.version, CONFIG_VERSION);
+        assert_eq!(migrated.time_format, config.time_format);
+    }
+
+    #[test]
+    fn migrate_newer() {
+        let mut value = serde_json::to_value(Config::default()).unwrap();
+        value["version"] = (CONFIG_VERSION + 1).into();
+
+        assert!(Config::migrate(value).is_err());
+    }
+
+    #[test]
+    fn short_time_format() {
+        assert_eq!(TimePreset::TwelveHour.short_format(), "%-I:%M %p");
+        assert_eq!(TimePreset::TwentyFourHour.short_format(), "%H:%M");
+        assert_eq!(
+            TimePreset::Custom("%-I:%M:%S %p".to_string()).short_format(),
+            "%-I:%M %p"
+        );
+        assert_eq!(
+            TimePreset::Custom("%H.%M".to_string()).short_format(),
+            "%H:%M"
+        );
+    }
+
+    #[test]
+    fn window_geometry_clamped() {
+        let geometry = WindowGeometry {
+            width: 50.0,
+            height: f32::NAN,
+            position: Some((-3000.0, 100.0)),
+        };
+
+        assert_eq!(geometry.size(), MIN_WINDOW_SIZE);
+        assert_eq!(geometry.position(), None);
+
+        let geometry = WindowGeometry {
+            width: 1280.0,
+            height: 720.0,
+            position: Some((40.0, 60.0)),
+        };
+
+        assert_eq!(geometry.size(), Size::new(1280.0, 720.0));
+        assert_eq!(geometry.position(), Some(Point::new(40.0, 60.0)));
+    }
+
+    #[test]
+    fn collection_ids() {
+        assert_eq!(parse_collection_id("1053828"), Some("1053828"));
+        assert_eq!(parse_collection_id(" 1053828 "), Some("1053828"));
+        assert_eq!(parse_collection_id("8zJ-0Jk1_qE"), Some("8zJ-0Jk1_qE"));
+        assert_eq!(
+            parse_collection_id("https://unsplash.com/collections/1053828/tabliss-official"),
+            Some("1053828")
+        );
+        assert_eq!(
+            parse_collection_id("unsplash.com/collections/8zJ-0Jk1_qE?utm_source=x"),
+            Some("8zJ-0Jk1_qE")
+        );
+
+        assert_eq!(parse_collection_id(""), None);
+        assert_eq!(parse_collection_id("105382a"), None);
+        assert_eq!(parse_collection_id("tabliss-official"), None);
+        assert_eq!(
+            parse_collection_id("https://unsplash.com/photos/8zJ-0Jk1_qE"),
+            None
+        );
+        assert_eq!(
+            parse_collection_id("https://unsplash.com/collections/"),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn corrupt_config_uses_backup() {
+        let dir = std::env::temp_dir().join(format!("fjordgard-test-{}", std::process::id()));
+        let path = dir.join("config.json");
+
+        let config = Config {
+            clock_size: 123,
+            ..Default::default()
+        };
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(config.save_to(&path))
+            .unwrap();
+        assert_eq!(Config::load_from(&path).unwrap().clock_size, 123);
+
+        // cut off mid-write
+        std::fs::write(&path, r#"{"version":1,"clock_si"#).unwrap();
+        assert_eq!(Config::load_from(&path).unwrap().clock_size, 123);
+
+        std::fs::remove_file(with_suffix(&path, ".bak")).unwrap();
+        assert!(Config::load_from(&path).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,124 @@
+use std::path::PathBuf;
+
+use chrono::{TimeDelta, Utc};
+use fjordgard_weather::{
+    Error,
+    model::{
+        Forecast, ForecastOptions, PrecipitationUnit, SpeedUnit, TemperatureUnit, WeatherModel,
+    },
+    provider::{BoxFuture, WeatherProvider},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+/// Cached forecasts older than this are not shown.
+const MAX_AGE: TimeDelta = TimeDelta::hours(1);
+
+/// The request options that change the values in a forecast.
+#[derive(Serialize, Deserialize, PartialEq)]
+struct CachedOptions {
+    temperature_unit: Option<TemperatureUnit>,
+    wind_speed_unit: Option<SpeedUnit>,
+    precipitation_unit: Option<PrecipitationUnit>,
+    models: Option<Vec<WeatherModel>>,
+}
+
+impl CachedOptions {
+    fn new(opt: Option<&ForecastOptions>) -> Self {
+        Self {
+            temperature_unit: opt.and_then(|o| o.temperature_unit),
+            wind_speed_unit: opt.and_then(|o| o.wind_speed_unit),
+            precipitation_unit: opt.and_then(|o| o.precipitation_unit),
+            models: opt.and_then(|o| o.models.clone()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedForecast {
+    /// Unix timestamp of the fetch.
+    fetched_at: i64,
+    /// Requested coordinates, Open-Meteo snaps the ones in the forecast to
+    /// its grid.
+    latitude: f64,
+    longitude: f64,
+    options: CachedOptions,
+    forecast: Forecast,
+}
+
+/// The last successful forecast on disk, used as the final fallback while
+/// offline.
+pub struct ForecastCache;
+
+impl ForecastCache {
+    pub const NAME: &str = "cache";
+
+    fn path() -> PathBuf {
+        Config::cache_path("forecast.json")
+    }
+
+    pub async fn store(
+        latitude: f64,
+        longitude: f64,
+        opt: Option<&ForecastOptions>,
+        forecast: Forecast,
+    ) -> anyhow::Result<()> {
+        let path = Self::path();
+
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+
+        let cached = CachedForecast {
+            fetched_at: Utc::now().timestamp(),
+            latitude,
+            longitude,
+            options: CachedOptions::new(opt),
+            forecast,
+        };
+
+        tokio::fs::write(path, serde_json::to_string(&cached)?).await?;
+
+        Ok(())
+    }
+
+    async fn load(
+        latitude: f64,
+        longitude: f64,
+        opt: Option<ForecastOptions>,
+    ) -> Result<Forecast, Error> {
+        let data = tokio::fs::read_to_string(Self::path())
+            .await
+            .map_err(|_| Error::Unavailable)?;
+        // a cache we can't read is as good as no cache
+        let cached: CachedForecast = serde_json::from_str(&data).map_err(|_| Error::Unavailable)?;
+
+        let age = Utc::now().timestamp() - cached.fetched_at;
+
+        if cached.latitude != latitude
+            || cached.longitude != longitude
+            || cached.options != CachedOptions::new(opt.as_ref())
+            || age > MAX_AGE.num_seconds()
+        {
+            return Err(Error::Unavailable);
+        }
+
+        Ok(cached.forecast)
+    }
+}
+
+impl WeatherProvider for ForecastCache {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn forecast<'a>(
+        &'a self,
+        latitude: f64,
+        longitude: f64,
+        opt: Option<ForecastOptions>,
+    ) -> BoxFuture<'a, Result<Forecast, Error>> {
+        Box::pin(Self::load(latitude, longitude, opt))
+    }
+}
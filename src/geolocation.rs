@@ -0,0 +1,103 @@
+#[cfg(not(target_arch = "wasm32"))]
+mod imp {
+    use anyhow::bail;
+    use serde::Deserialize;
+
+    const IP_LOCATION_URL: &str = "https://ipapi.co/json/";
+    const USER_AGENT: &str = concat!("fjordgard/", env!("CARGO_PKG_VERSION"));
+
+    #[derive(Deserialize)]
+    struct IpLocation {
+        #[serde(default)]
+        error: bool,
+        #[serde(default)]
+        reason: Option<String>,
+        latitude: Option<f64>,
+        longitude: Option<f64>,
+    }
+
+    /// Approximate coordinates of the user, from their IP address.
+    pub async fn current() -> anyhow::Result<(f64, f64)> {
+        let client = reqwest::Client::builder().user_agent(USER_AGENT).build()?;
+        let location: IpLocation = client
+            .get(IP_LOCATION_URL)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if location.error {
+            bail!(
+                "failed to locate IP address: {}",
+                location.reason.as_deref().unwrap_or("unknown error")
+            );
+        }
+
+        match (location.latitude, location.longitude) {
+            (Some(latitude), Some(longitude)) => Ok((latitude, longitude)),
+            _ => bail!("IP address has no known location"),
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod imp {
+    use std::{cell::RefCell, rc::Rc};
+
+    use anyhow::anyhow;
+    use iced::futures::channel::oneshot;
+    use wasm_bindgen::{JsCast, closure::Closure};
+    use web_sys::{Position, PositionError};
+
+    /// Coordinates of the user from the browser, which asks for permission.
+    pub async fn current() -> anyhow::Result<(f64, f64)> {
+        let geolocation = web_sys::window()
+            .ok_or_else(|| anyhow!("no window"))?
+            .navigator()
+            .geolocation()
+            .map_err(|_| anyhow!("geolocation is not supported"))?;
+
+        let (tx, rx) = oneshot::channel();
+        let tx = Rc::new(RefCell::new(Some(tx)));
+
+        let success = {
+            let tx = tx.clone();
+            Closure::<dyn FnMut(Position)>::new(move |pos: Position| {
+                let coords = pos.coords();
+
+                if let Some(tx) = tx.borrow_mut().take() {
+                    let _ = tx.send(Ok((coords.latitude(), coords.longitude())));
+                }
+            })
+        };
+
+        let error = Closure::<dyn FnMut(PositionError)>::new(move |err: PositionError| {
+            let reason = match err.code() {
+                PositionError::PERMISSION_DENIED => String::from("location permission denied"),
+                _ => err.message(),
+            };
+
+            if let Some(tx) = tx.borrow_mut().take() {
+                let _ = tx.send(Err(reason));
+            }
+        });
+
+        geolocation
+            .get_current_position_with_error_callback(
+                success.as_ref().unchecked_ref(),
+                Some(error.as_ref().unchecked_ref()),
+            )
+            .map_err(|_| anyhow!("failed to request location"))?;
+
+        // the callbacks have to stay alive until one of them is called
+        let result = rx.await;
+        drop((success, error));
+
+        result
+            .map_err(|_| anyhow!("location request was cancelled"))?
+            .map_err(|e| anyhow!(e))
+    }
+}
+
+pub use imp::current;
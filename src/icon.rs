@@ -1,7 +1,11 @@
+use std::{collections::BTreeSet, sync::Mutex};
+
+use fjordgard_weather::model::WeatherCode;
 use iced::{
     Color, Element, Length, Theme,
     widget::{Svg, button, svg},
 };
+use log::warn;
 use rust_embed::Embed;
 
 #[derive(Embed)]
@@ -9,27 +13,83 @@ use rust_embed::Embed;
 #[prefix = "icons/"]
 struct Icon;
 
-pub fn icon<'a>(path: &str) -> Svg<'a, iced::Theme> {
-    let bytes = Icon::get(path).unwrap().data;
+/// Shown in place of icons missing from the embedded folder, included
+/// separately so it can't go missing itself.
+const FALLBACK: &[u8] = include_bytes!("../icons/question.svg");
+
+/// Missing icons already logged, views are rebuilt constantly.
+static MISSING: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Weather icon shown when the condition is unknown.
+pub const UNKNOWN_WEATHER: &str = "icons/weather/100-0.svg";
+
+macro_rules! weather_icon_path {
+    ($group:expr, $is_day:expr, [$($known:literal),*]) => {
+        match ($group, $is_day) {
+            $(
+                ($known, false) => Some(concat!("icons/weather/", $known, "-0.svg")),
+                ($known, true) => Some(concat!("icons/weather/", $known, "-1.svg")),
+            )*
+            _ => None,
+        }
+    };
+}
+
+fn weather_icon_path(group: u8, is_day: bool) -> Option<&'static str> {
+    weather_icon_path!(
+        group,
+        is_day,
+        [0, 1, 2, 3, 45, 51, 61, 71, 77, 80, 95, 96, 100]
+    )
+    .filter(|path| Icon::get(path).is_some())
+}
+
+/// Path of the embedded icon for a weather condition, trying the other time
+/// of day and then the unknown icon when there is no exact match.
+pub fn weather_icon(code: WeatherCode, is_day: bool) -> &'static str {
+    let group = code.icon_group();
+
+    weather_icon_path(group, is_day)
+        .or_else(|| weather_icon_path(group, !is_day))
+        .or_else(|| weather_icon_path(100, is_day))
+        .unwrap_or(UNKNOWN_WEATHER)
+}
+
+/// An embedded icon in white.
+pub fn icon<'a>(path: &str) -> Svg<'a, Theme> {
+    icon_colored(path, Color::WHITE)
+}
+
+/// An embedded icon tinted with `color`.
+pub fn icon_colored<'a>(path: &str, color: Color) -> Svg<'a, Theme> {
+    let bytes = match Icon::get(path) {
+        Some(file) => file.data,
+        None => {
+            if let Ok(mut missing) = MISSING.lock()
+                && missing.insert(path.to_string())
+            {
+                warn!("missing icon {path}");
+            }
+
+            FALLBACK.into()
+        }
+    };
 
     svg(svg::Handle::from_memory(bytes))
         .height(Length::Fixed(16.0))
         .width(Length::Fixed(16.0))
-        .style(white)
+        .style(move |_, _| svg::Style { color: Some(color) })
 }
 
 pub fn icon_button<'a, Message: 'a + Clone>(
     handle: &str,
+    color: Option<Color>,
     on_press: Message,
 ) -> Element<'a, Message> {
-    button(icon(handle))
-        .style(button::text)
-        .on_press(on_press)
-        .into()
-}
+    let icon = match color {
+        Some(color) => icon_colored(handle, color),
+        None => icon(handle),
+    };
 
-fn white(_theme: &Theme, _status: svg::Status) -> svg::Style {
-    svg::Style {
-        color: Some(Color::WHITE),
-    }
+    button(icon).style(button::text).on_press(on_press).into()
 }
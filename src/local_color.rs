@@ -0,0 +1,90 @@
+use std::{collections::HashMap, path::PathBuf, time::UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+/// Side of the thumbnail averaged for the color, plenty for a solid fill.
+const THUMBNAIL_SIZE: u32 = 32;
+
+#[derive(Serialize, Deserialize)]
+struct CachedColor {
+    /// Modification time of the image, as seconds since the Unix epoch.
+    modified: u64,
+    color: [u8; 3],
+}
+
+/// Average colors of local background images, keyed by path, so a
+/// placeholder can be shown before the image itself is read and decoded.
+pub struct LocalColorCache;
+
+impl LocalColorCache {
+    fn path() -> PathBuf {
+        Config::cache_path("local_colors.json")
+    }
+
+    async fn modified(image: &str) -> Option<u64> {
+        let modified = tokio::fs::metadata(image).await.ok()?.modified().ok()?;
+
+        Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs())
+    }
+
+    async fn load() -> HashMap<String, CachedColor> {
+        // a cache we can't read is as good as no cache
+        match tokio::fs::read_to_string(Self::path()).await {
+            Ok(data) => serde_json::from_str(&data).unwrap_or_default(),
+            Err(_) => HashMap::new(),
+        }
+    }
+
+    /// The cached color of `image`, if it hasn't changed since.
+    pub async fn get(image: String) -> Option<[u8; 3]> {
+        let modified = Self::modified(&image).await?;
+        let cached = Self::load().await.remove(&image)?;
+
+        (cached.modified == modified).then_some(cached.color)
+    }
+
+    /// Compute and cache the color of `image` from its contents, unless the
+    /// cached one is still up to date. Returns the color either way.
+    pub async fn update(image: String, bytes: Vec<u8>) -> anyhow::Result<[u8; 3]> {
+        let path = Self::path();
+        let modified = Self::modified(&image)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("failed to read modification time"))?;
+
+        let mut colors = Self::load().await;
+
+        if let Some(cached) = colors.get(&image).filter(|c| c.modified == modified) {
+            return Ok(cached.color);
+        }
+
+        let color = tokio::task::spawn_blocking(move || average_color(&bytes)).await??;
+        colors.insert(image, CachedColor { modified, color });
+
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+
+        tokio::fs::write(path, serde_json::to_string(&colors)?).await?;
+
+        Ok(color)
+    }
+}
+
+fn average_color(bytes: &[u8]) -> anyhow::Result<[u8; 3]> {
+    let thumbnail = image::load_from_memory(bytes)?
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .to_rgb8();
+
+    let mut sum = [0u64; 3];
+    for pixel in thumbnail.pixels() {
+        for (total, channel) in sum.iter_mut().zip(pixel.0) {
+            *total += channel as u64;
+        }
+    }
+
+    let count = (thumbnail.width() as u64 * thumbnail.height() as u64).max(1);
+
+    Ok(sum.map(|total| (total / count) as u8))
+}
@@ -1,47 +1,223 @@
-use std::{cell::RefCell, rc::Rc, sync::Arc};
+use std::{cell::RefCell, rc::Rc, sync::Arc, time::Duration};
 
-use chrono::{
-    DateTime, Local,
-    format::{Item, StrftimeItems},
-};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, TimeDelta, format::Item};
+use chrono_tz::Tz;
 use fjordgard_weather::{
-    MeteoClient,
-    model::{CurrentVariable, Forecast, ForecastOptions},
+    Error as MeteoError, MeteoClient, MeteoErrorKind,
+    model::{
+        CurrentVariable, DailyData, DailyVariable, Forecast, ForecastOptions, HourlyVariable,
+        PrecipitationUnit, SpeedUnit, TemperatureUnit, WeatherModel, WindDirection,
+    },
+    provider::{FallbackProvider, WeatherProvider},
 };
-#[cfg(not(target_arch = "wasm32"))]
-use iced::font::Weight;
 use iced::{
-    Color, Element, Font, Length, Size, Subscription, Task, time,
-    widget::{center, column, container, horizontal_space, row, stack, text},
+    Alignment, Color, Element, Event, Font, Length, Radians, Size, Subscription, Task, Theme,
+    clipboard, event, keyboard, time,
+    widget::{button, center, column, container, horizontal_space, row, stack, text, tooltip},
     window,
 };
+#[cfg(not(target_arch = "wasm32"))]
+use iced::{Point, daemon, font::Weight};
 
 use background::BackgroundHandle;
-use config::{BackgroundMode, Config};
-use icon::{icon, icon_button};
-use log::{debug, error};
+#[cfg(not(target_arch = "wasm32"))]
+use config::WindowGeometry;
+use config::{Config, TimePreset, parse_time_format};
+use icon::{icon_button, icon_colored, weather_icon};
+use log::{debug, error, warn};
 
 mod background;
 mod config;
+#[cfg(not(target_arch = "wasm32"))]
+mod forecast_cache;
+mod geolocation;
 mod icon;
+#[cfg(not(target_arch = "wasm32"))]
+mod local_color;
 mod settings;
+#[cfg(not(target_arch = "wasm32"))]
+mod tray;
+
+const FORECAST_BACKOFF: TimeDelta = TimeDelta::hours(1);
+const WEATHER_TIMEOUT: Duration = Duration::from_secs(30);
+const DAILY_FORECAST_DAYS: usize = 5;
+/// How far ahead to look for rain.
+const RAIN_LOOKAHEAD: TimeDelta = TimeDelta::hours(6);
+/// Chance of precipitation, in percent, from which rain is shown as expected.
+const RAIN_PROBABILITY: f64 = 50.0;
+/// Most random delay before the first periodic refresh, so instances started
+/// together don't all hit the APIs at once.
+const REFRESH_JITTER: Duration = Duration::from_secs(30);
+/// Unsplash rotations start this much later still, so they don't line up with
+/// forecast refreshes on the same interval.
+const ROTATION_OFFSET: Duration = Duration::from_secs(45);
+/// Highest pixel density photos are fetched at, past this they get huge for
+/// little visible gain.
+const MAX_PHOTO_SCALE: f32 = 3.0;
+
+/// Whether `items` display anything more precise than minutes.
+#[cfg(not(target_arch = "wasm32"))]
+fn has_seconds(items: &[Item]) -> bool {
+    use chrono::format::{Fixed, Numeric};
+
+    items.iter().any(|item| {
+        matches!(
+            item,
+            Item::Numeric(
+                Numeric::Second | Numeric::Nanosecond | Numeric::Timestamp,
+                _
+            ) | Item::Fixed(
+                Fixed::Nanosecond
+                    | Fixed::Nanosecond3
+                    | Fixed::Nanosecond6
+                    | Fixed::Nanosecond9
+                    | Fixed::RFC2822
+                    | Fixed::RFC3339
+            )
+        )
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn config_reloads() -> impl iced::futures::Stream<Item = Message> {
+    use iced::futures::StreamExt;
+
+    Config::watch().map(|r| Message::ConfigReloaded(Box::new(r.map_err(|e| e.to_string()))))
+}
+
+/// Ticks at the start of every minute.
+#[cfg(not(target_arch = "wasm32"))]
+fn minute_ticks() -> impl iced::futures::Stream<Item = Message> {
+    use chrono::Timelike;
+
+    iced::futures::stream::unfold((), |_| async {
+        let now = Local::now();
+        let elapsed = Duration::from_secs(now.second().into())
+            + Duration::from_nanos(now.nanosecond().min(999_999_999).into());
+
+        // land slightly past the boundary so we never display the previous
+        // minute
+        tokio::time::sleep(Duration::from_secs(60) - elapsed + Duration::from_millis(5)).await;
+
+        Some((Message::Tick(Local::now()), ()))
+    })
+}
+
+/// Ticks every `period`, the first tick coming `delay` late so the period
+/// itself stays exact.
+#[cfg(not(target_arch = "wasm32"))]
+fn every_after(period: Duration, delay: Duration) -> Subscription<()> {
+    use tokio::time::{Instant, MissedTickBehavior, interval_at};
+
+    let ticks = iced::futures::stream::unfold(None, move |interval| async move {
+        // the stream is built on every `subscription` call, only start the
+        // clock once it actually runs
+        let mut interval = interval.unwrap_or_else(|| {
+            let mut interval = interval_at(Instant::now() + period + delay, period);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            interval
+        });
+
+        interval.tick().await;
+
+        Some(((), Some(interval)))
+    });
+
+    Subscription::run_with_id((period, delay), ticks)
+}
+
+/// wasm has no timer we can delay, so ticks start right away.
+#[cfg(target_arch = "wasm32")]
+fn every_after(period: Duration, _delay: Duration) -> Subscription<()> {
+    time::every(period).map(|_| ())
+}
+
+fn clock_font(family: Option<&str>) -> Font {
+    #[cfg_attr(target_arch = "wasm32", allow(unused_mut))]
+    let mut font = match family {
+        // fonts need a static name, this only happens when the family changes
+        Some(family) => Font::with_name(Box::leak(family.to_string().into_boxed_str())),
+        None => Font::DEFAULT,
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        font.weight = Weight::Bold;
+    }
+
+    font
+}
+
+fn parse_timezones(names: &[String]) -> Vec<Tz> {
+    names
+        .iter()
+        .filter_map(|name| match name.parse() {
+            Ok(tz) => Some(tz),
+            Err(_) => {
+                warn!("ignoring unknown time zone {name}");
+                None
+            }
+        })
+        .collect()
+}
 
 pub struct Fjordgard {
     config: Rc<RefCell<Config>>,
     meteo: Arc<MeteoClient>,
+    weather: Arc<FallbackProvider>,
     time: DateTime<Local>,
     background: BackgroundHandle,
     format_string: String,
     format_parsed: Vec<Item<'static>>,
+    extra_timezones: Vec<Tz>,
+    font_family: Option<String>,
+    clock_font: Font,
+    clock_size: u16,
 
     settings_window: Option<settings::Settings>,
     settings_id: Option<window::Id>,
     main_window: window::Id,
     main_window_size: Size,
+    /// Scale factor of the main window's display.
+    /// `None` until the main window reports it.
+    scale_factor: Option<f32>,
+    #[cfg(not(target_arch = "wasm32"))]
+    transparent: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    tray: Option<tray::Tray>,
+    #[cfg(not(target_arch = "wasm32"))]
+    main_window_hidden: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    fullscreen: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    main_window_position: Option<Point>,
+    /// When the main window last moved or resized, its geometry is saved
+    /// once it settles.
+    #[cfg(not(target_arch = "wasm32"))]
+    pending_geometry_save: Option<time::Instant>,
 
     coordinate_pair: Option<(f64, f64)>,
+    weather_model: Option<WeatherModel>,
+    weather_units: (TemperatureUnit, SpeedUnit, PrecipitationUnit),
+    forecast_backoff_until: Option<DateTime<Local>>,
+    /// Id of the latest forecast request, older responses are ignored.
+    forecast_request: u64,
+    last_forecast_error: Option<String>,
+    /// The forecast shown came from the offline cache.
+    forecast_stale: bool,
+    daily_forecast: Option<DailyData>,
+    /// Today's sunrise and sunset at the forecast location.
+    sun_times: Option<(DateTime<FixedOffset>, DateTime<FixedOffset>)>,
+    /// Start of the next hour rain is likely, see [`RAIN_LOOKAHEAD`].
+    rain_at: Option<DateTime<FixedOffset>>,
+    /// Current wind speed in m/s, converted to the configured unit when shown.
+    wind: Option<(f64, WindDirection)>,
     forecast_text: String,
-    forecast_icon: String,
+    forecast_icon: &'static str,
+    /// Delays the first forecast refresh, picked once per run.
+    forecast_jitter: Duration,
+    /// Delays the first rotation, picked once per run.
+    rotation_jitter: Duration,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -55,18 +231,69 @@ pub enum MediaControl {
 pub enum Message {
     Tick(DateTime<Local>),
     Media(MediaControl),
+    TogglePhotoInfo,
     OpenSettings,
 
     SettingsOpened(window::Id),
     MainWindowOpened,
     WindowClosed(window::Id),
     WindowResized((window::Id, Size)),
+    ScaleFactor(f32),
+    #[cfg(not(target_arch = "wasm32"))]
+    WindowMoved(window::Id, Point),
+    #[cfg(not(target_arch = "wasm32"))]
+    GeometryTick(time::Instant),
 
     Settings(settings::Message),
     Background(background::Message),
+    /// A shortcut pressed in the given window.
+    Shortcut(window::Id, Box<Message>),
+    CopyTime,
+    ToggleFullscreen,
 
     RequestForecastUpdate,
-    ForecastUpdate(Box<Result<Forecast, String>>),
+    ForecastUpdate {
+        request: u64,
+        forecast: Box<Result<Forecast, String>>,
+        stale: bool,
+    },
+    ForecastRateLimited(u64, String),
+    ForecastTimedOut(u64),
+    #[cfg(not(target_arch = "wasm32"))]
+    ConfigReloaded(Box<Result<Config, String>>),
+    #[cfg(not(target_arch = "wasm32"))]
+    Tray(tray::Action),
+}
+
+/// Whether canned API responses were asked for, to work on the app offline
+/// and without an Unsplash key.
+#[cfg(not(target_arch = "wasm32"))]
+fn mock_requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--mock")
+        || std::env::var_os("FJORDGARD_MOCK").is_some()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn mock_requested() -> bool {
+    false
+}
+
+/// A random duration up to `max`.
+fn jitter(max: Duration) -> Duration {
+    use std::hash::{BuildHasher, RandomState};
+
+    // std's hasher keys are random, no need for a whole crate
+    let random = RandomState::new().hash_one(Local::now().timestamp_subsec_nanos());
+
+    max.mul_f64((random % 1000) as f64 / 1000.0)
+}
+
+fn clamp_photo_scale(scale: f32) -> f32 {
+    if scale.is_finite() {
+        scale.clamp(1.0, MAX_PHOTO_SCALE)
+    } else {
+        1.0
+    }
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -78,41 +305,134 @@ fn window_open(_settings: window::Settings) -> (window::Id, Task<window::Id>) {
 
 impl Fjordgard {
     fn new() -> (Self, Task<Message>) {
-        let settings = window::Settings::default();
+        let mut config = Config::load().unwrap_or_else(|e| {
+            error!("failed to load config, using defaults: {e}");
+
+            Config {
+                read_only: true,
+                ..Default::default()
+            }
+        });
+        config.mock = mock_requested();
+
+        if config.mock {
+            warn!("mock mode, serving canned weather and Unsplash responses");
+        }
+
+        #[cfg_attr(target_arch = "wasm32", allow(unused_mut))]
+        let mut settings = window::Settings {
+            transparent: config.transparent_window,
+            ..Default::default()
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(geometry) = config.window {
+            settings.size = geometry.size();
+
+            if let Some(position) = geometry.position() {
+                settings.position = window::Position::Specific(position);
+            }
+        }
+
         let main_window_size = settings.size;
+        #[cfg(not(target_arch = "wasm32"))]
+        let transparent = settings.transparent;
+        #[cfg(not(target_arch = "wasm32"))]
+        let fullscreen = config.start_fullscreen;
 
         #[cfg(not(target_arch = "wasm32"))]
         let (id, open) = window::open(settings);
         #[cfg(target_arch = "wasm32")]
         let (id, open) = window_open(settings);
 
-        let config = Config::load().unwrap();
+        let (format_string, format_parsed) = match parse_time_format(config.time_format.format()) {
+            Some(items) => (config.time_format.format().to_string(), items),
+            None => {
+                warn!("invalid time format {}", config.time_format.format());
+                let format = TimePreset::default().format().to_string();
+                let items = parse_time_format(&format).unwrap_or_default();
+
+                (format, items)
+            }
+        };
+        let extra_timezones = parse_timezones(&config.extra_timezones);
+        let font_family = config.font_family.clone();
+        let clock_font = clock_font(font_family.as_deref());
+        let clock_size = config.clock_size;
+        let weather_model = config.weather_model.clone();
+        let weather_units = config.weather_units();
 
-        let format_string = config.time_format.clone();
-        let format_parsed = StrftimeItems::new_lenient(&format_string)
-            .parse_to_owned()
-            .unwrap();
+        let meteo_client = || {
+            MeteoClient::builder()
+                .timeout(WEATHER_TIMEOUT)
+                .mock(config.mock)
+                .build()
+                .unwrap()
+        };
+        let meteo = meteo_client();
+        let weather = FallbackProvider::new(meteo_client());
+        #[cfg(not(target_arch = "wasm32"))]
+        let weather = weather.with_fallback(forecast_cache::ForecastCache);
+        // the display's scale factor is only known once the window opens
+        let photo_size = main_window_size * config.photo_scale.map_or(1.0, clamp_photo_scale);
+        let (background, task) = BackgroundHandle::new(&config, photo_size);
 
-        let meteo = MeteoClient::new(None).unwrap();
-        let (background, task) = BackgroundHandle::new(&config, main_window_size);
+        #[cfg(not(target_arch = "wasm32"))]
+        let tray = match tray::Tray::new() {
+            Ok(tray) => Some(tray),
+            Err(e) => {
+                warn!("continuing without a system tray: {e}");
+                None
+            }
+        };
 
         (
             Self {
                 config: Rc::new(RefCell::new(config)),
                 meteo: Arc::new(meteo),
+                weather: Arc::new(weather),
                 time: Local::now(),
                 background,
                 format_string,
                 format_parsed,
+                extra_timezones,
+                font_family,
+                clock_font,
+                clock_size,
 
                 settings_window: None,
                 settings_id: None,
                 main_window: id,
                 main_window_size,
+                scale_factor: None,
+                #[cfg(not(target_arch = "wasm32"))]
+                transparent,
+                #[cfg(not(target_arch = "wasm32"))]
+                tray,
+                #[cfg(not(target_arch = "wasm32"))]
+                main_window_hidden: false,
+                #[cfg(not(target_arch = "wasm32"))]
+                fullscreen,
+                #[cfg(not(target_arch = "wasm32"))]
+                main_window_position: None,
+                #[cfg(not(target_arch = "wasm32"))]
+                pending_geometry_save: None,
 
                 coordinate_pair: None,
+                weather_model,
+                weather_units,
+                forecast_backoff_until: None,
+                forecast_request: 0,
+                last_forecast_error: None,
+                forecast_stale: false,
+                daily_forecast: None,
+                sun_times: None,
+                rain_at: None,
+                wind: None,
                 forecast_text: String::from("Weather unknown"),
-                forecast_icon: String::from("icons/weather/100-0.svg"),
+                forecast_icon: icon::UNKNOWN_WEATHER,
+                forecast_jitter: jitter(REFRESH_JITTER),
+                rotation_jitter: ROTATION_OFFSET + jitter(REFRESH_JITTER),
             },
             Task::batch([
                 open.map(|_| Message::MainWindowOpened),
@@ -131,11 +451,138 @@ impl Fjordgard {
         }
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
+    fn theme(&self, window_id: window::Id) -> Theme {
+        match &self.settings_window {
+            // preview changes before they're saved
+            Some(settings) if window_id != self.main_window => settings.theme(),
+            _ => self.config.borrow().theme.theme(),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn theme(&self) -> Theme {
+        match &self.settings_window {
+            Some(settings) => settings.theme(),
+            None => self.config.borrow().theme.theme(),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn style(&self, theme: &Theme) -> daemon::Appearance {
+        let palette = theme.palette();
+
+        daemon::Appearance {
+            background_color: if self.transparent {
+                Color::TRANSPARENT
+            } else {
+                palette.background
+            },
+            text_color: palette.text,
+        }
+    }
+
     #[cfg(target_arch = "wasm32")]
     fn title(&self) -> String {
         String::from("Fjordgard")
     }
 
+    /// Save the main window's geometry so it's restored on the next launch.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_geometry(&mut self) -> Task<Message> {
+        if self.fullscreen {
+            return Task::none();
+        }
+
+        let geometry = WindowGeometry {
+            width: self.main_window_size.width,
+            height: self.main_window_size.height,
+            position: self.main_window_position.map(|p| (p.x, p.y)),
+        };
+
+        let mut config = self.config.borrow_mut();
+        if config.window == Some(geometry) {
+            return Task::none();
+        }
+
+        config.window = Some(geometry);
+        let config = config.clone();
+
+        Task::future(async move {
+            if let Err(e) = config.save().await {
+                error!("failed to save window geometry: {e}");
+            }
+        })
+        .discard()
+    }
+
+    /// Quit, saving the main window's geometry first.
+    fn exit(&mut self) -> Task<Message> {
+        #[cfg(not(target_arch = "wasm32"))]
+        return self.save_geometry().chain(iced::exit());
+
+        #[cfg(target_arch = "wasm32")]
+        iced::exit()
+    }
+
+    /// Size of the main window in physical pixels, which background photos
+    /// are fetched at.
+    fn photo_size(&self) -> Size {
+        let scale = self
+            .config
+            .borrow()
+            .photo_scale
+            .or(self.scale_factor)
+            .unwrap_or(1.0);
+
+        self.main_window_size * clamp_photo_scale(scale)
+    }
+
+    /// Apply `self.config` after it changed.
+    fn apply_config(&mut self) -> Task<Message> {
+        let config = self.config.borrow();
+        self.extra_timezones = parse_timezones(&config.extra_timezones);
+        self.clock_size = config.clock_size;
+
+        if self.font_family != config.font_family {
+            self.font_family = config.font_family.clone();
+            self.clock_font = clock_font(config.font_family.as_deref());
+        }
+
+        let config_format = config.time_format.format();
+
+        if self.format_string != config_format {
+            match parse_time_format(config_format) {
+                Some(items) => {
+                    self.format_string = config_format.to_string();
+                    self.format_parsed = items;
+                }
+                None => warn!("invalid time format {config_format}, keeping the previous one"),
+            }
+        }
+
+        let background_task = self
+            .background
+            .load_config(&config, self.photo_size())
+            .map(Message::Background);
+
+        let new_pair = config.location.as_ref().map(|l| (l.latitude, l.longitude));
+
+        if new_pair != self.coordinate_pair
+            || config.weather_model != self.weather_model
+            || config.weather_units() != self.weather_units
+        {
+            self.coordinate_pair = new_pair;
+            self.weather_model = config.weather_model.clone();
+            self.weather_units = config.weather_units();
+            // a different request, give it a chance right away
+            self.forecast_backoff_until = None;
+            Task::batch([background_task, Task::done(Message::RequestForecastUpdate)])
+        } else {
+            background_task
+        }
+    }
+
     fn update(&mut self, msg: Message) -> Task<Message> {
         match msg {
             Message::Tick(time) => {
@@ -154,6 +601,20 @@ impl Fjordgard {
                     Task::done(Message::Background(background::Message::PauseUnsplash))
                 }
             },
+            Message::TogglePhotoInfo => {
+                let mut config = self.config.borrow_mut();
+                config.show_photo_info = !config.show_photo_info;
+                self.background.set_show_photo_info(config.show_photo_info);
+
+                let config = config.clone();
+
+                Task::future(async move {
+                    if let Err(e) = config.save().await {
+                        error!("failed to save config: {e}");
+                    }
+                })
+                .discard()
+            }
             Message::OpenSettings => {
                 if self.settings_window.is_none() {
                     #[cfg(not(target_arch = "wasm32"))]
@@ -176,9 +637,36 @@ impl Fjordgard {
                     Task::none()
                 }
             }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::Tray(action) => match action {
+                tray::Action::ToggleWindow => {
+                    self.main_window_hidden = !self.main_window_hidden;
+
+                    let mode = if self.main_window_hidden {
+                        window::Mode::Hidden
+                    } else if self.fullscreen {
+                        window::Mode::Fullscreen
+                    } else {
+                        window::Mode::Windowed
+                    };
+
+                    window::change_mode(self.main_window, mode)
+                }
+                tray::Action::Next => Task::done(Message::Media(MediaControl::Next)),
+                tray::Action::Pause => Task::done(Message::Media(MediaControl::Pause)),
+                tray::Action::Quit => {
+                    self.tray = None;
+                    self.exit()
+                }
+            },
             Message::WindowClosed(id) => {
                 if self.main_window == id {
-                    iced::exit()
+                    #[cfg(not(target_arch = "wasm32"))]
+                    {
+                        self.tray = None;
+                    }
+
+                    self.exit()
                 } else {
                     self.settings_window = None;
                     Task::none()
@@ -190,34 +678,72 @@ impl Fjordgard {
                 }
 
                 self.main_window_size = size;
+                self.background.resize(self.photo_size());
+
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    self.pending_geometry_save = Some(time::Instant::now());
+                }
+
+                // moving to another display changes the size too
+                window::get_scale_factor(id).map(Message::ScaleFactor)
+            }
+            Message::ScaleFactor(scale_factor) => {
+                let startup = self.scale_factor.is_none();
+
+                if self.scale_factor != Some(scale_factor) {
+                    self.scale_factor = Some(scale_factor);
+
+                    // the first photo is usually still being looked up, so
+                    // fetch it at this size rather than downloading it twice
+                    if startup {
+                        self.background.set_size(self.photo_size());
+                    } else {
+                        self.background.resize(self.photo_size());
+                    }
+                }
 
                 Task::none()
             }
-            Message::Settings(settings::Message::Committed) => {
-                let config = self.config.borrow();
-                let config_format = &config.time_format;
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::WindowMoved(id, position) => {
+                if self.main_window == id {
+                    self.main_window_position = Some(position);
+                    self.pending_geometry_save = Some(time::Instant::now());
+                }
 
-                if &self.format_string != config_format {
-                    self.format_string = config_format.clone();
-                    self.format_parsed = StrftimeItems::new_lenient(config_format)
-                        .parse_to_owned()
-                        .unwrap();
+                Task::none()
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::GeometryTick(now) => match self.pending_geometry_save {
+                Some(at) if now.duration_since(at) >= background::RESIZE_DEBOUNCE => {
+                    self.pending_geometry_save = None;
+                    self.save_geometry()
+                }
+                _ => Task::none(),
+            },
+            Message::Settings(settings::Message::Committed) => self.apply_config(),
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::ConfigReloaded(res) => match *res {
+                Err(e) => {
+                    error!("failed to reload config, keeping the current one: {e}");
+                    Task::none()
                 }
+                Ok(mut config) => {
+                    // never saved, so the file on disk doesn't have it
+                    config.mock = self.config.borrow().mock;
 
-                let background_task = self
-                    .background
-                    .load_config(&config, self.main_window_size)
-                    .map(Message::Background);
+                    // most likely written by the app itself
+                    if config == *self.config.borrow() {
+                        return Task::none();
+                    }
 
-                let new_pair = config.location.as_ref().map(|l| (l.latitude, l.longitude));
+                    debug!("config changed on disk, reloading");
 
-                if new_pair != self.coordinate_pair {
-                    self.coordinate_pair = new_pair;
-                    Task::batch([background_task, Task::done(Message::RequestForecastUpdate)])
-                } else {
-                    background_task
+                    *self.config.borrow_mut() = config;
+                    self.apply_config()
                 }
-            }
+            },
             #[cfg(target_arch = "wasm32")]
             Message::Settings(settings::Message::ToBackground(msg)) => {
                 Task::done(Message::Background(msg))
@@ -248,6 +774,19 @@ impl Fjordgard {
                 }
             }
             Message::Background(msg) => self.background.update(msg).map(Message::Background),
+            Message::CopyTime => {
+                let time = self.time.format_with_items(self.format_parsed.iter());
+                self.background.show_status("Time copied");
+
+                clipboard::write(time.to_string())
+            }
+            Message::Shortcut(id, msg) => {
+                if id == self.main_window {
+                    Task::done(*msg)
+                } else {
+                    Task::none()
+                }
+            }
             Message::SettingsOpened(id) => {
                 debug!("settings window opened");
                 self.settings_id = Some(id);
@@ -255,117 +794,205 @@ impl Fjordgard {
             }
             Message::MainWindowOpened => {
                 debug!("main window opened");
+
+                let scale_factor =
+                    window::get_scale_factor(self.main_window).map(Message::ScaleFactor);
+
+                #[cfg(not(target_arch = "wasm32"))]
+                if self.fullscreen {
+                    return Task::batch([
+                        scale_factor,
+                        window::change_mode(self.main_window, window::Mode::Fullscreen),
+                    ]);
+                }
+
+                scale_factor
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::ToggleFullscreen => {
+                self.fullscreen = !self.fullscreen;
+
+                let mode = if self.fullscreen {
+                    window::Mode::Fullscreen
+                } else {
+                    window::Mode::Windowed
+                };
+
+                window::change_mode(self.main_window, mode)
+            }
+            #[cfg(target_arch = "wasm32")]
+            Message::ToggleFullscreen => {
+                toggle_browser_fullscreen();
                 Task::none()
             }
             Message::RequestForecastUpdate => {
+                if self
+                    .forecast_backoff_until
+                    .is_some_and(|until| Local::now() < until)
+                {
+                    debug!("skipping forecast update, rate limited");
+                    return Task::none();
+                }
+
+                self.forecast_request += 1;
+                let request = self.forecast_request;
+
                 let config = self.config.borrow();
                 if let Some(location) = &config.location {
-                    let meteo = self.meteo.clone();
+                    let weather = self.weather.clone();
                     let (latitude, longitude) = (location.latitude, location.longitude);
+                    let models = config.weather_model.clone().map(|m| vec![m]);
+                    let (temperature_unit, wind_speed_unit, precipitation_unit) =
+                        config.weather_units();
+
+                    let options = ForecastOptions {
+                        current: Some(vec![
+                            CurrentVariable::Temperature2m,
+                            CurrentVariable::IsDay,
+                            CurrentVariable::WeatherCode,
+                            CurrentVariable::WindSpeed10m,
+                            CurrentVariable::WindDirection10m,
+                        ]),
+                        daily: Some(vec![
+                            DailyVariable::Temperature2mMax,
+                            DailyVariable::Temperature2mMin,
+                            DailyVariable::WeatherCode,
+                            DailyVariable::Sunrise,
+                            DailyVariable::Sunset,
+                        ]),
+                        hourly: Some(vec![
+                            HourlyVariable::Precipitation,
+                            HourlyVariable::PrecipitationProbability,
+                        ]),
+                        forecast_days: Some(DAILY_FORECAST_DAYS),
+                        // hourly data starts at the current hour
+                        forecast_hours: Some(RAIN_LOOKAHEAD.num_hours() as usize + 1),
+                        timezone: Some(String::from("auto")),
+                        temperature_unit: Some(temperature_unit),
+                        wind_speed_unit: Some(wind_speed_unit),
+                        precipitation_unit: Some(precipitation_unit),
+                        models,
+                        ..Default::default()
+                    };
 
                     Task::future(async move {
-                        meteo
-                            .forecast_single(
+                        let res = weather
+                            .forecast(latitude, longitude, Some(options.clone()))
+                            .await;
+
+                        let provider = weather.last_answered();
+
+                        if let Some(provider) = &provider {
+                            debug!("forecast answered by {provider}");
+                        }
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        let stale =
+                            provider.as_deref() == Some(forecast_cache::ForecastCache::NAME);
+                        #[cfg(target_arch = "wasm32")]
+                        let stale = false;
+
+                        #[cfg(not(target_arch = "wasm32"))]
+                        if !stale
+                            && let Ok(forecast) = &res
+                            && let Err(e) = forecast_cache::ForecastCache::store(
                                 latitude,
                                 longitude,
-                                Some(ForecastOptions {
-                                    current: Some(vec![
-                                        CurrentVariable::Temperature2m,
-                                        CurrentVariable::IsDay,
-                                        CurrentVariable::WeatherCode,
-                                    ]),
-                                    ..Default::default()
-                                }),
+                                Some(&options),
+                                forecast.clone(),
                             )
                             .await
+                        {
+                            warn!("failed to cache forecast: {e}");
+                        }
+
+                        (res, stale)
+                    })
+                    .map(move |(r, stale)| match r {
+                        Err(MeteoError::Meteo {
+                            reason,
+                            kind: MeteoErrorKind::RateLimited,
+                        }) => Message::ForecastRateLimited(request, reason),
+                        Err(MeteoError::Timeout) => Message::ForecastTimedOut(request),
+                        r => Message::ForecastUpdate {
+                            request,
+                            forecast: Box::new(r.map_err(|e| e.to_string())),
+                            stale,
+                        },
                     })
-                    .map(|r| Message::ForecastUpdate(Box::new(r.map_err(|e| e.to_string()))))
                 } else {
                     self.forecast_text = String::from("Weather unknown");
-                    self.forecast_icon = String::from("icons/weather/100-0.svg");
+                    self.forecast_icon = icon::UNKNOWN_WEATHER;
+                    self.last_forecast_error = None;
+                    self.forecast_stale = false;
+                    self.daily_forecast = None;
+                    self.sun_times = None;
+                    self.rain_at = None;
+                    self.wind = None;
 
                     Task::none()
                 }
             }
-            Message::ForecastUpdate(res) => match *res {
+            Message::ForecastRateLimited(request, _)
+            | Message::ForecastTimedOut(request)
+            | Message::ForecastUpdate { request, .. }
+                if request != self.forecast_request =>
+            {
+                debug!("dropping stale forecast response {request}");
+                Task::none()
+            }
+            Message::ForecastRateLimited(_, reason) => {
+                warn!("forecast rate limited: {reason}");
+
+                self.forecast_backoff_until = Some(Local::now() + FORECAST_BACKOFF);
+                self.forecast_text = String::from("Weather rate limited");
+                self.forecast_icon = icon::UNKNOWN_WEATHER;
+                self.last_forecast_error = Some(reason);
+
+                Task::none()
+            }
+            Message::ForecastTimedOut(_) => {
+                warn!("forecast request timed out");
+
+                self.forecast_text = String::from("Weather request timed out");
+                self.forecast_icon = icon::UNKNOWN_WEATHER;
+                self.last_forecast_error = Some(String::from("request timed out"));
+
+                Task::none()
+            }
+            Message::ForecastUpdate {
+                forecast, stale, ..
+            } => match *forecast {
                 Err(e) => {
                     error!("failed to load forecast: {e}");
+                    self.last_forecast_error = Some(e);
                     Task::none()
                 }
-                Ok(forecast) => {
-                    let forecast = || -> Option<(String, String)> {
+                Ok(mut forecast) => {
+                    self.last_forecast_error = None;
+                    self.forecast_stale = stale;
+                    self.sun_times = forecast.today_sunrise().zip(forecast.today_sunset());
+                    self.rain_at = forecast.next_precipitation(RAIN_LOOKAHEAD, RAIN_PROBABILITY);
+                    self.wind = forecast
+                        .current_wind_speed_in(SpeedUnit::MetersPerSecond)
+                        .zip(forecast.current.as_ref().and_then(|c| c.wind_direction()));
+                    self.daily_forecast = forecast.daily.take();
+
+                    let forecast = || -> Option<(String, &'static str)> {
                         let current = forecast.current?;
                         let units = forecast.current_units?;
 
                         let temperature = current.data.get(&CurrentVariable::Temperature2m)?;
                         let temperature_units = units.get(&CurrentVariable::Temperature2m)?;
 
-                        let is_day = *current.data.get(&CurrentVariable::IsDay)? as u64;
-                        let weather_code = *current.data.get(&CurrentVariable::WeatherCode)? as u64;
-
-                        let condition_text = match weather_code {
-                            0 => {
-                                if is_day == 0 {
-                                    "Clear"
-                                } else {
-                                    "Sunny"
-                                }
-                            }
-                            1 => {
-                                if is_day == 0 {
-                                    "Mainly clear"
-                                } else {
-                                    "Mainly sunny"
-                                }
-                            }
-                            2 => "Partly cloudy",
-                            3 => "Overcast",
-                            45 => "Foggy",
-                            48 => "Rime fog",
-                            51 => "Light drizzle",
-                            53 => "Drizzle",
-                            55 => "Heavy drizzle",
-                            56 => "Light freezing drizzle",
-                            57 => "Freezing drizzle",
-                            61 => "Light rain",
-                            63 => "Rain",
-                            65 => "Heavy rain",
-                            66 => "Light freezing rain",
-                            67 => "Freezing rain",
-                            71 => "Light snow",
-                            73 => "Snow",
-                            75 => "Heavy snow",
-                            77 => "Snow grains",
-                            80 => "Light showers",
-                            81 => "Showers",
-                            82 => "Heavy showers",
-                            85 => "Light snow showers",
-                            86 => "Snow showers",
-                            95 => "Thunderstorm",
-                            96 => "Light thunderstorm with hail",
-                            99 => "Thunderstorm with hail",
-                            _ => "Unknown",
-                        };
-
-                        let icon_condition = match weather_code {
-                            0 => 0,
-                            1 => 1,
-                            2 => 2,
-                            3 => 3,
-                            45 | 48 => 45,
-                            51 | 53 | 55 | 56 | 57 => 51,
-                            61 | 63 | 65 | 66 | 67 => 61,
-                            71 | 73 | 75 => 71,
-                            77 => 77,
-                            80 | 81 | 82 | 85 | 86 => 80,
-                            95 => 95,
-                            96 | 99 => 96,
-                            _ => 100,
-                        };
+                        let is_day = current.is_day()?;
+                        let weather_code = current.weather_code()?;
+
+                        let condition_text = weather_code.description(is_day);
 
                         Some((
                             format!("{temperature}{temperature_units} {condition_text}"),
-                            format!("icons/weather/{icon_condition}-{is_day}.svg"),
+                            weather_icon(weather_code, is_day),
                         ))
                     };
 
@@ -381,20 +1008,25 @@ impl Fjordgard {
     }
 
     #[cfg(not(target_arch = "wasm32"))]
-    fn view(&self, window_id: window::Id) -> Element<Message> {
+    fn view(&self, window_id: window::Id) -> Element<'_, Message> {
         if self.main_window == window_id {
             self.view_main()
         } else {
-            self.settings_window
-                .as_ref()
-                .expect("settings window")
-                .view()
-                .map(Message::Settings)
+            // the settings window is opaque even when the main window isn't
+            container(
+                self.settings_window
+                    .as_ref()
+                    .expect("settings window")
+                    .view()
+                    .map(Message::Settings),
+            )
+            .style(|theme: &Theme| container::background(theme.palette().background))
+            .into()
         }
     }
 
     #[cfg(target_arch = "wasm32")]
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
         if let Some(settings) = &self.settings_window {
             settings.view().map(Message::Settings)
         } else {
@@ -402,47 +1034,259 @@ impl Fjordgard {
         }
     }
 
-    fn view_main(&self) -> Element<Message> {
-        #[cfg_attr(target_arch = "wasm32", allow(unused_mut))]
-        let mut bold = Font::DEFAULT;
-        #[cfg(not(target_arch = "wasm32"))]
-        {
-            bold.weight = Weight::Bold;
+    /// Color of the clock and weather, readable against the background.
+    fn text_color(&self) -> Color {
+        self.background.text_color()
+    }
+
+    /// A hint with the time until rain is expected.
+    fn view_rain(&self) -> Option<Element<'_, Message>> {
+        let rain_at = self.rain_at?;
+        let lead = rain_at.signed_duration_since(self.time);
+
+        // the forecast hasn't been refreshed since
+        if lead < -TimeDelta::hours(1) {
+            return None;
         }
 
+        let hint = match lead.num_minutes() {
+            ..=0 => String::from("Rain expected now"),
+            minutes @ 1..60 => format!("Rain in ~{minutes} min"),
+            minutes => format!("Rain in ~{}h", (minutes + 30) / 60),
+        };
+
+        Some(
+            row![
+                icon_colored("icons/droplet.svg", self.text_color())
+                    .height(Length::Fixed(16.0))
+                    .width(Length::Fixed(16.0)),
+                text(hint).color(self.text_color()).size(16),
+            ]
+            .spacing(5)
+            .align_y(Alignment::Center)
+            .into(),
+        )
+    }
+
+    fn view_daily(&self) -> Option<Element<'_, Message>> {
+        let daily = self.daily_forecast.as_ref()?;
+
+        let codes = daily.weather_codes()?;
+        let highs = daily.data.get(&DailyVariable::Temperature2mMax)?;
+        let lows = daily.data.get(&DailyVariable::Temperature2mMin)?;
+
+        // Open-Meteo may return fewer days than requested
+        let days = daily
+            .time
+            .iter()
+            .zip(codes)
+            .zip(highs.iter().zip(lows))
+            .map(|((date, code), (high, low))| {
+                let day = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                    .map(|d| d.format("%a").to_string())
+                    .unwrap_or_default();
+
+                let icon = code.map_or(icon::UNKNOWN_WEATHER, |c| weather_icon(c, true));
+                let temperature =
+                    |t: &Option<f64>| t.map_or(String::from("–"), |t| format!("{t:.0}°"));
+
+                column![
+                    text(day).color(self.text_color()).size(16),
+                    icon_colored(icon, self.text_color())
+                        .height(Length::Fixed(24.0))
+                        .width(Length::Fixed(24.0)),
+                    text(format!("{}/{}", temperature(high), temperature(low)))
+                        .color(self.text_color())
+                        .size(16),
+                ]
+                .spacing(2)
+                .align_x(Alignment::Center)
+                .into()
+            })
+            .collect::<Vec<Element<Message>>>();
+
+        if days.is_empty() {
+            return None;
+        }
+
+        Some(row(days).spacing(20).padding(10).into())
+    }
+
+    fn view_main(&self) -> Element<'_, Message> {
         let time_text = self.time.format_with_items(self.format_parsed.iter());
-        let time_widget = text(time_text.to_string())
-            .size(200)
-            .font(bold)
-            .color(Color::WHITE)
-            .width(Length::Fill)
-            .center();
-
-        let weather_widget = container(row![
-            icon(&self.forecast_icon)
+        let time_widget = button(
+            text(time_text.to_string())
+                .size(self.clock_size)
+                .font(self.clock_font)
+                .color(self.text_color())
+                .width(Length::Fill)
+                .center(),
+        )
+        .style(button::text)
+        .padding(0)
+        .on_press(Message::CopyTime);
+
+        let forecast_color = if self.last_forecast_error.is_some() {
+            Color::from_rgb8(0xff, 0x8a, 0x80)
+        } else if self.forecast_stale {
+            self.text_color().scale_alpha(0.6)
+        } else {
+            self.text_color()
+        };
+
+        let mut weather_row = row![
+            icon_colored(self.forecast_icon, self.text_color())
                 .height(Length::Fixed(32.0))
                 .width(Length::Fixed(32.0)),
             horizontal_space().width(Length::Fixed(7.25)),
-            text(&self.forecast_text).color(Color::WHITE).size(25)
-        ])
-        .center_x(Length::Fill);
-
-        let settings = icon_button("icons/settings.svg", Message::OpenSettings);
+            text(&self.forecast_text).color(forecast_color).size(25),
+        ]
+        .align_y(Alignment::Center);
 
-        let mut main_column = column![settings, center(column![time_widget, weather_widget])];
+        if let Some((speed, direction)) = self.wind {
+            let unit = self.config.borrow().wind_unit;
+            let speed = SpeedUnit::MetersPerSecond.convert(speed, unit);
 
-        if self.background.mode == BackgroundMode::Unsplash {
-            main_column = main_column.push(
-                container(
-                    row![
-                        icon_button("icons/previous.svg", Message::Media(MediaControl::Previous)),
-                        icon_button("icons/pause.svg", Message::Media(MediaControl::Pause)),
-                        icon_button("icons/next.svg", Message::Media(MediaControl::Next)),
-                    ]
-                    .spacing(5),
+            weather_row = weather_row
+                .push(horizontal_space().width(Length::Fixed(14.5)))
+                .push(
+                    icon_colored("icons/wind-arrow.svg", self.text_color())
+                        .rotation(Radians(direction.arrow_rotation()))
+                        .height(Length::Fixed(20.0))
+                        .width(Length::Fixed(20.0)),
                 )
-                .center_x(Length::Fill),
+                .push(
+                    text(format!("{speed:.0} {unit} {}", direction.compass_point()))
+                        .color(forecast_color)
+                        .size(25),
+                );
+        }
+
+        let mut weather_widget: Element<Message> = weather_row
+            .push(icon_button(
+                "icons/refresh.svg",
+                Some(self.text_color()),
+                Message::RequestForecastUpdate,
+            ))
+            .into();
+
+        if let Some(err) = &self.last_forecast_error {
+            weather_widget = tooltip(
+                weather_widget,
+                container(text(format!("Failed to update weather: {err}")))
+                    .padding(5)
+                    .style(container::rounded_box),
+                tooltip::Position::Bottom,
             )
+            .into();
+        } else if self.forecast_stale {
+            weather_widget = tooltip(
+                weather_widget,
+                container(text("Offline, showing the last known weather"))
+                    .padding(5)
+                    .style(container::rounded_box),
+                tooltip::Position::Bottom,
+            )
+            .into();
+        }
+
+        let weather_widget = container(weather_widget).center_x(Length::Fill);
+
+        let mut clock_column = column![time_widget];
+
+        for tz in self.extra_timezones.iter() {
+            let zone_time = self
+                .time
+                .with_timezone(tz)
+                .format_with_items(self.format_parsed.iter());
+            let zone_name = tz.name().rsplit('/').next().unwrap_or_default();
+
+            clock_column = clock_column.push(
+                text(format!("{zone_time} {}", zone_name.replace('_', " ")))
+                    .size(40)
+                    .color(self.text_color())
+                    .width(Length::Fill)
+                    .center(),
+            );
+        }
+
+        clock_column = clock_column.push(weather_widget);
+
+        if let Some(rain) = self.view_rain() {
+            clock_column = clock_column.push(container(rain).center_x(Length::Fill));
+        }
+
+        if let Some((sunrise, sunset)) = self.sun_times {
+            let format = self.config.borrow().time_format.short_format();
+
+            clock_column = clock_column.push(
+                text(format!(
+                    "Sunrise {} · Sunset {}",
+                    sunrise.format(format),
+                    sunset.format(format)
+                ))
+                .color(self.text_color())
+                .size(16)
+                .width(Length::Fill)
+                .center(),
+            );
+        }
+
+        if let Some(daily) = self.view_daily() {
+            clock_column = clock_column.push(container(daily).center_x(Length::Fill));
+        }
+
+        let settings = icon_button(
+            "icons/settings.svg",
+            Some(self.text_color()),
+            Message::OpenSettings,
+        );
+
+        let mut main_column = column![settings, center(clock_column)];
+
+        if self.background.mode.is_unsplash() {
+            let pause_icon = if self.background.unsplash_paused() {
+                "icons/play.svg"
+            } else {
+                "icons/pause.svg"
+            };
+
+            let mut controls = row![
+                icon_button(
+                    "icons/previous.svg",
+                    Some(self.text_color()),
+                    Message::Media(MediaControl::Previous)
+                ),
+                icon_button(
+                    pause_icon,
+                    Some(self.text_color()),
+                    Message::Media(MediaControl::Pause)
+                ),
+                icon_button(
+                    "icons/next.svg",
+                    Some(self.text_color()),
+                    Message::Media(MediaControl::Next)
+                ),
+                icon_button(
+                    "icons/info.svg",
+                    Some(self.text_color()),
+                    Message::TogglePhotoInfo
+                ),
+                icon_button(
+                    "icons/download.svg",
+                    Some(self.text_color()),
+                    Message::Background(background::Message::SaveWallpaper)
+                ),
+            ]
+            .spacing(5)
+            .align_y(Alignment::Center);
+
+            if let Some((current, total)) = self.background.unsplash_position() {
+                controls =
+                    controls.push(text(format!("{current} / {total}")).color(self.text_color()));
+            }
+
+            main_column = main_column.push(container(controls).center_x(Length::Fill))
         }
 
         stack![
@@ -455,17 +1299,107 @@ impl Fjordgard {
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        Subscription::batch([
-            time::every(time::Duration::from_secs(1)).map(|_| Message::Tick(Local::now())),
-            time::every(time::Duration::from_secs(60 * 15)).map(|_| Message::RequestForecastUpdate),
-            time::every(time::Duration::from_secs(60 * 15))
+        // without seconds on screen there's no need to wake up every second,
+        // wasm has no timer we can align with so always ticks every second
+        #[cfg(not(target_arch = "wasm32"))]
+        let clock = if has_seconds(&self.format_parsed) {
+            time::every(time::Duration::from_secs(1)).map(|_| Message::Tick(Local::now()))
+        } else {
+            Subscription::run(minute_ticks)
+        };
+        #[cfg(target_arch = "wasm32")]
+        let clock = time::every(time::Duration::from_secs(1)).map(|_| Message::Tick(Local::now()));
+
+        let config = self.config.borrow();
+
+        let forecast = match config.forecast_interval_secs {
+            0 => Subscription::none(),
+            secs => every_after(Duration::from_secs(secs), self.forecast_jitter)
+                .map(|_| Message::RequestForecastUpdate),
+        };
+
+        let rotation = match config.rotation_interval_secs {
+            0 => Subscription::none(),
+            secs => every_after(Duration::from_secs(secs), self.rotation_jitter)
                 .map(|_| Message::Background(background::Message::RequestUnsplash(1))),
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let geometry = if self.pending_geometry_save.is_some() {
+            time::every(Duration::from_millis(100)).map(Message::GeometryTick)
+        } else {
+            Subscription::none()
+        };
+
+        Subscription::batch([
+            clock,
+            #[cfg(not(target_arch = "wasm32"))]
+            geometry,
+            #[cfg(not(target_arch = "wasm32"))]
+            Subscription::run(config_reloads),
+            forecast,
+            rotation,
+            self.background.subscription().map(Message::Background),
+            #[cfg(not(target_arch = "wasm32"))]
+            Subscription::run(tray::events).map(Message::Tray),
             window::close_events().map(Message::WindowClosed),
             window::resize_events().map(Message::WindowResized),
+            event::listen_with(shortcut),
+            #[cfg(not(target_arch = "wasm32"))]
+            event::listen_with(window_moved),
         ])
     }
 }
 
+/// `o` opens the current photo's page, `p` its photographer's profile, `c`
+/// copies the time and F11 toggles fullscreen, unless a widget like a text
+/// input took the key.
+fn shortcut(event: Event, status: event::Status, id: window::Id) -> Option<Message> {
+    let Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) = event else {
+        return None;
+    };
+
+    if status == event::Status::Captured || !modifiers.is_empty() {
+        return None;
+    }
+
+    let msg = match key.as_ref() {
+        keyboard::Key::Character("o") => Message::Background(background::Message::OpenPhotoPage),
+        keyboard::Key::Character("p") => {
+            Message::Background(background::Message::OpenAuthorProfile)
+        }
+        keyboard::Key::Character("c") => Message::CopyTime,
+        keyboard::Key::Named(keyboard::key::Named::F11) => Message::ToggleFullscreen,
+        _ => return None,
+    };
+
+    Some(Message::Shortcut(id, Box::new(msg)))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn window_moved(event: Event, _status: event::Status, id: window::Id) -> Option<Message> {
+    match event {
+        Event::Window(window::Event::Moved(position)) => Some(Message::WindowMoved(id, position)),
+        _ => None,
+    }
+}
+
+/// Fullscreen the page, browsers only allow it in response to user input.
+#[cfg(target_arch = "wasm32")]
+fn toggle_browser_fullscreen() {
+    let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+        return;
+    };
+
+    if document.fullscreen_element().is_some() {
+        document.exit_fullscreen();
+    } else if let Some(root) = document.document_element()
+        && let Err(e) = root.request_fullscreen()
+    {
+        warn!("browser refused fullscreen: {e:?}");
+    }
+}
+
 fn main() -> iced::Result {
     #[cfg(not(target_arch = "wasm32"))]
     {
@@ -473,6 +1407,8 @@ fn main() -> iced::Result {
 
         iced::daemon(Fjordgard::title, Fjordgard::update, Fjordgard::view)
             .subscription(Fjordgard::subscription)
+            .style(Fjordgard::style)
+            .theme(Fjordgard::theme)
             .run_with(Fjordgard::new)
     }
 
@@ -483,6 +1419,7 @@ fn main() -> iced::Result {
 
         iced::application(Fjordgard::title, Fjordgard::update, Fjordgard::view)
             .subscription(Fjordgard::subscription)
+            .theme(Fjordgard::theme)
             .run_with(Fjordgard::new)
     }
 }
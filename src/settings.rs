@@ -1,16 +1,74 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::{cell::RefCell, rc::Rc, sync::Arc};
 
-use fjordgard_weather::{MeteoClient, model::Location};
+use chrono::Local;
+use chrono_tz::Tz;
+use fjordgard_unsplash::{Error as UnsplashError, UnsplashClient};
+use fjordgard_weather::{
+    MeteoClient,
+    model::{
+        GeocodeOptions, Location, PrecipitationUnit, SpeedUnit, TemperatureUnit, WeatherModel,
+    },
+};
 use iced::{
-    Background, Border, Color, Element, Length, Task, Theme,
-    widget::{button, column, combo_box, container, row, scrollable, text, text_input, tooltip},
+    Alignment, Background, Border, Color, Element, Length, Task, Theme,
+    widget::{
+        button, checkbox, column, combo_box, container, row, scrollable, slider, text, text_input,
+        tooltip,
+    },
 };
-use log::error;
+use log::{debug, error};
 #[cfg(not(target_arch = "wasm32"))]
 use rfd::{AsyncFileDialog, FileHandle};
 use strum::VariantArray;
 
-use crate::config::{self, BackgroundMode, Config};
+use crate::{
+    background::parse_gradient,
+    config::{
+        self, AppTheme, BackgroundFit, BackgroundMode, Config, PhotoOrder, TimePreset,
+        parse_time_format,
+    },
+    geolocation,
+};
+
+/// How many places a location search returns.
+const GEOCODE_RESULTS: usize = 10;
+/// Location results shown before the list scrolls.
+const VISIBLE_RESULTS: usize = 5;
+const RESULT_HEIGHT: f32 = 32.0;
+
+/// Parse a coordinate, accepting a comma as the decimal separator and
+/// rejecting values outside of `-limit..=limit`.
+fn parse_coordinate(s: &str, limit: f64) -> Option<f64> {
+    let s = s.trim();
+    let value: f64 = match s.parse() {
+        Ok(value) => value,
+        Err(_) if !s.contains('.') && s.matches(',').count() == 1 => {
+            s.replace(',', ".").parse().ok()?
+        }
+        Err(_) => return None,
+    };
+
+    (value.abs() <= limit).then_some(value)
+}
+
+/// The system language as the two letter code Open-Meteo expects.
+fn geocode_language() -> Option<String> {
+    let locale = sys_locale::get_locale()?;
+    let language = locale.split(['-', '_']).next()?.to_lowercase();
+
+    (!language.is_empty()).then_some(language)
+}
+
+#[derive(Debug, Clone, PartialEq, strum::Display, strum::VariantArray)]
+pub enum TimeFormat {
+    #[strum(to_string = "12-hour")]
+    TwelveHour,
+    #[strum(to_string = "24-hour")]
+    TwentyFourHour,
+    Custom,
+}
 
 #[derive(Debug, Clone, PartialEq, strum::Display, strum::VariantArray)]
 pub enum WeatherLocation {
@@ -20,6 +78,51 @@ pub enum WeatherLocation {
     Coordinates,
 }
 
+/// A refresh interval in seconds, `0` meaning never.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval(u64);
+
+impl Interval {
+    const PRESETS: [Interval; 5] = [
+        Interval(60 * 5),
+        Interval(60 * 15),
+        Interval(60 * 30),
+        Interval(60 * 60),
+        Interval(0),
+    ];
+
+    /// The presets, plus `current` if it was set to something else by hand.
+    fn options(current: u64) -> Vec<Interval> {
+        let mut options = Self::PRESETS.to_vec();
+
+        if !options.contains(&Interval(current)) {
+            options.insert(0, Interval(current));
+        }
+
+        options
+    }
+}
+
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "Never"),
+            secs if secs % 3600 == 0 => write!(f, "{} h", secs / 3600),
+            secs if secs % 60 == 0 => write!(f, "{} min", secs / 60),
+            secs => write!(f, "{secs} s"),
+        }
+    }
+}
+
+/// Outcome of testing the Unsplash API key.
+#[derive(Debug, Clone)]
+pub enum KeyCheck {
+    Testing,
+    Valid,
+    Invalid,
+    Failed(String),
+}
+
 #[derive(Debug, Clone)]
 pub struct LocationRow {
     name: String,
@@ -30,15 +133,49 @@ pub struct LocationRow {
 pub struct Settings {
     config: Rc<RefCell<Config>>,
     meteo: Arc<MeteoClient>,
+    time_formats: combo_box::State<TimeFormat>,
     backgrounds: combo_box::State<BackgroundMode>,
+    themes: combo_box::State<AppTheme>,
+    fits: combo_box::State<BackgroundFit>,
+    photo_orders: combo_box::State<PhotoOrder>,
+    rotation_intervals: combo_box::State<Interval>,
+    forecast_intervals: combo_box::State<Interval>,
+    weather_models: combo_box::State<WeatherModel>,
+    temperature_units: combo_box::State<TemperatureUnit>,
+    wind_units: combo_box::State<SpeedUnit>,
+    precipitation_units: combo_box::State<PrecipitationUnit>,
     locations: combo_box::State<WeatherLocation>,
     #[cfg(not(target_arch = "wasm32"))]
     file_selector_open: bool,
 
-    time_format: String,
+    time_format: TimeFormat,
+    custom_time_format: String,
     background_mode: BackgroundMode,
     background: String,
     unsplash_key: String,
+    unsplash_key_check: Option<KeyCheck>,
+    /// See [`Config::mock`], no key is needed.
+    mock: bool,
+    overlay_opacity: f32,
+    theme: AppTheme,
+    background_fit: BackgroundFit,
+    data_saver: bool,
+    photo_order: PhotoOrder,
+    text_color: String,
+    font_family: String,
+    clock_size: u16,
+    rotation_interval: Interval,
+    forecast_interval: Interval,
+    weather_model: WeatherModel,
+    temperature_unit: TemperatureUnit,
+    wind_unit: SpeedUnit,
+    precipitation_unit: PrecipitationUnit,
+    #[cfg(not(target_arch = "wasm32"))]
+    background_blur: f32,
+    #[cfg(not(target_arch = "wasm32"))]
+    transparent_window: bool,
+    #[cfg(not(target_arch = "wasm32"))]
+    start_fullscreen: bool,
 
     location: WeatherLocation,
     name: String,
@@ -47,14 +184,56 @@ pub struct Settings {
 
     location_results: Vec<LocationRow>,
     location_fetch_error: Option<String>,
+    /// Place name of the entered coordinates, if it could be resolved.
+    resolved_place: Option<String>,
+
+    extra_timezones: Vec<String>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    transfer_error: Option<String>,
+    /// Warns that the config is kept at [`Config::fallback_path`].
+    #[cfg(not(target_arch = "wasm32"))]
+    storage_notice: Option<String>,
+    save_error: Option<String>,
 }
 
+/// Set once the fallback config location has been pointed out, which only
+/// needs doing once per run.
+#[cfg(not(target_arch = "wasm32"))]
+static FALLBACK_NOTICE_SHOWN: AtomicBool = AtomicBool::new(false);
+
 #[derive(Debug, Clone)]
 pub enum Message {
-    TimeFormat(String),
+    TimeFormat(TimeFormat),
+    CustomTimeFormat(String),
+    Timezone(usize, String),
+    AddTimezone,
+    RemoveTimezone(usize),
     BackgroundMode(BackgroundMode),
     Background(String),
     UnsplashKey(String),
+    TestUnsplashKey,
+    UnsplashKeyTested(KeyCheck),
+    OverlayOpacity(f32),
+    Theme(AppTheme),
+    BackgroundFit(BackgroundFit),
+    DataSaver(bool),
+    PhotoOrder(PhotoOrder),
+    TextColor(String),
+    FontFamily(String),
+    ClockSize(u16),
+    RotationInterval(Interval),
+    ForecastInterval(Interval),
+    WeatherModel(WeatherModel),
+    TemperatureUnit(TemperatureUnit),
+    WindUnit(SpeedUnit),
+    PrecipitationUnit(PrecipitationUnit),
+    #[cfg(not(target_arch = "wasm32"))]
+    BackgroundBlur(f32),
+    #[cfg(not(target_arch = "wasm32"))]
+    TransparentWindow(bool),
+    #[cfg(not(target_arch = "wasm32"))]
+    StartFullscreen(bool),
     Location(WeatherLocation),
     Name(String),
     NameSubmitted,
@@ -62,10 +241,22 @@ pub enum Message {
     LocationSelected(LocationRow),
     Latitude(String),
     Longitude(String),
+    UseCurrentLocation,
+    CurrentLocation(Result<LocationRow, String>),
+    CoordinatesSubmitted,
+    PlaceResolved((f64, f64), Option<String>),
     #[cfg(not(target_arch = "wasm32"))]
     FileSelector,
     #[cfg(not(target_arch = "wasm32"))]
     FileSelected(Option<FileHandle>),
+    #[cfg(not(target_arch = "wasm32"))]
+    Export,
+    #[cfg(not(target_arch = "wasm32"))]
+    Exported(Result<(), String>),
+    #[cfg(not(target_arch = "wasm32"))]
+    Import,
+    #[cfg(not(target_arch = "wasm32"))]
+    Imported(Result<Option<Box<Config>>, String>),
     Save,
     CloseSettings,
 
@@ -103,18 +294,71 @@ impl Settings {
             })
             .unwrap_or(WeatherLocation::Disabled);
 
+        let weather_model = original_config
+            .weather_model
+            .clone()
+            .unwrap_or(WeatherModel::BestMatch);
+        let mut weather_models = WeatherModel::KNOWN.to_vec();
+        if !weather_models.contains(&weather_model) {
+            weather_models.push(weather_model.clone());
+        }
+
+        let custom_time_format = original_config.time_format.format().to_string();
+        let time_format = match original_config.time_format {
+            TimePreset::TwelveHour => TimeFormat::TwelveHour,
+            TimePreset::TwentyFourHour => TimeFormat::TwentyFourHour,
+            TimePreset::Custom(_) => TimeFormat::Custom,
+        };
+
         Self {
             config,
             meteo,
+            time_formats: combo_box::State::new(TimeFormat::VARIANTS.to_vec()),
             backgrounds: combo_box::State::new(BackgroundMode::VARIANTS.to_vec()),
+            themes: combo_box::State::new(AppTheme::VARIANTS.to_vec()),
+            fits: combo_box::State::new(BackgroundFit::VARIANTS.to_vec()),
+            photo_orders: combo_box::State::new(PhotoOrder::VARIANTS.to_vec()),
+            rotation_intervals: combo_box::State::new(Interval::options(
+                original_config.rotation_interval_secs,
+            )),
+            forecast_intervals: combo_box::State::new(Interval::options(
+                original_config.forecast_interval_secs,
+            )),
+            weather_models: combo_box::State::new(weather_models),
+            temperature_units: combo_box::State::new(TemperatureUnit::VARIANTS.to_vec()),
+            wind_units: combo_box::State::new(SpeedUnit::VARIANTS.to_vec()),
+            precipitation_units: combo_box::State::new(PrecipitationUnit::VARIANTS.to_vec()),
             locations: combo_box::State::new(WeatherLocation::VARIANTS.to_vec()),
             #[cfg(not(target_arch = "wasm32"))]
             file_selector_open: false,
 
-            time_format: original_config.time_format,
+            time_format,
+            custom_time_format,
             background_mode: original_config.background_mode,
             background: original_config.background,
             unsplash_key: original_config.unsplash_key.unwrap_or_default(),
+            unsplash_key_check: None,
+            mock: original_config.mock,
+            overlay_opacity: original_config.overlay_opacity,
+            theme: original_config.theme,
+            background_fit: original_config.background_fit,
+            data_saver: original_config.data_saver,
+            photo_order: original_config.photo_order,
+            text_color: original_config.text_color.unwrap_or_default(),
+            font_family: original_config.font_family.unwrap_or_default(),
+            clock_size: original_config.clock_size,
+            rotation_interval: Interval(original_config.rotation_interval_secs),
+            forecast_interval: Interval(original_config.forecast_interval_secs),
+            weather_model,
+            temperature_unit: original_config.temperature_unit,
+            wind_unit: original_config.wind_unit,
+            precipitation_unit: original_config.precipitation_unit,
+            #[cfg(not(target_arch = "wasm32"))]
+            background_blur: original_config.background_blur,
+            #[cfg(not(target_arch = "wasm32"))]
+            transparent_window: original_config.transparent_window,
+            #[cfg(not(target_arch = "wasm32"))]
+            start_fullscreen: original_config.start_fullscreen,
 
             location,
             latitude,
@@ -123,31 +367,228 @@ impl Settings {
 
             location_results: vec![],
             location_fetch_error: None,
+            resolved_place: None,
+
+            extra_timezones: original_config.extra_timezones,
+
+            #[cfg(not(target_arch = "wasm32"))]
+            transfer_error: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            storage_notice: (Config::using_fallback_path()
+                && !FALLBACK_NOTICE_SHOWN.swap(true, Ordering::Relaxed))
+            .then(|| {
+                format!(
+                    "No config directory found, settings are saved to {}",
+                    Config::fallback_path().display()
+                )
+            }),
+            save_error: None,
         }
     }
 
+    /// Apply `config` to the app and persist it.
+    fn commit(config: Config) -> Task<Message> {
+        Task::batch([
+            Task::done(Message::Committed),
+            Task::future(async move { config.save().await })
+                .map(|r| Message::Saved(r.map_err(|e| e.to_string()))),
+        ])
+    }
+
+    /// The entered coordinates, if both are valid numbers.
+    fn coordinates(&self) -> Option<(f64, f64)> {
+        Some((self.parsed_latitude()?, self.parsed_longitude()?))
+    }
+
+    fn parsed_latitude(&self) -> Option<f64> {
+        parse_coordinate(&self.latitude, 90.0)
+    }
+
+    fn parsed_longitude(&self) -> Option<f64> {
+        parse_coordinate(&self.longitude, 180.0)
+    }
+
+    /// Look up the name of the entered coordinates, leaving it blank on
+    /// failure as it's only informational.
+    fn resolve_place(&mut self) -> Task<Message> {
+        self.resolved_place = None;
+
+        let Some((latitude, longitude)) = self.coordinates() else {
+            return Task::none();
+        };
+
+        let meteo = self.meteo.clone();
+
+        Task::future(async move { meteo.reverse_geocode(latitude, longitude).await }).map(
+            move |r| {
+                let place = match r {
+                    Ok(location) => location.map(|l| l.display_name()),
+                    Err(e) => {
+                        debug!("failed to resolve coordinates: {e}");
+                        None
+                    }
+                };
+
+                Message::PlaceResolved((latitude, longitude), place)
+            },
+        )
+    }
+
     pub fn update(&mut self, msg: Message) -> Task<Message> {
         match msg {
             Message::TimeFormat(format) => {
                 self.time_format = format;
                 Task::none()
             }
+            Message::CustomTimeFormat(format) => {
+                self.custom_time_format = format;
+                Task::none()
+            }
+            Message::Timezone(i, name) => {
+                if let Some(tz) = self.extra_timezones.get_mut(i) {
+                    *tz = name;
+                }
+                Task::none()
+            }
+            Message::AddTimezone => {
+                self.extra_timezones.push(String::new());
+                Task::none()
+            }
+            Message::RemoveTimezone(i) => {
+                if i < self.extra_timezones.len() {
+                    self.extra_timezones.remove(i);
+                }
+                Task::none()
+            }
             Message::BackgroundMode(mode) => {
                 self.background = mode.default_background().to_string();
                 self.background_mode = mode;
                 Task::none()
             }
             Message::Background(background) => {
-                self.background = background;
+                // keep just the id of a pasted collection URL
+                self.background = match config::parse_collection_id(&background) {
+                    Some(id) if self.background_mode == BackgroundMode::Unsplash => id.to_string(),
+                    _ => background,
+                };
                 Task::none()
             }
             Message::UnsplashKey(key) => {
                 self.unsplash_key = key;
+                self.unsplash_key_check = None;
+                Task::none()
+            }
+            Message::TestUnsplashKey => {
+                self.unsplash_key_check = Some(KeyCheck::Testing);
+                let key = self.unsplash_key.clone();
+                let mock = self.mock;
+
+                Task::future(async move {
+                    let res = match UnsplashClient::builder().api_key(&key).mock(mock).build() {
+                        Ok(client) => client.verify_key().await,
+                        Err(e) => Err(e),
+                    };
+
+                    match res {
+                        Ok(()) => KeyCheck::Valid,
+                        Err(UnsplashError::InvalidAPIKey) => KeyCheck::Invalid,
+                        Err(e) => KeyCheck::Failed(e.to_string()),
+                    }
+                })
+                .map(Message::UnsplashKeyTested)
+            }
+            Message::UnsplashKeyTested(check) => {
+                // a response for a key that has since been edited
+                if self.unsplash_key_check.is_some() {
+                    if let KeyCheck::Failed(e) = &check {
+                        error!("failed to test unsplash key: {e}");
+                    }
+
+                    self.unsplash_key_check = Some(check);
+                }
+
+                Task::none()
+            }
+            Message::OverlayOpacity(opacity) => {
+                self.overlay_opacity = opacity;
+                Task::none()
+            }
+            Message::Theme(theme) => {
+                self.theme = theme;
+                Task::none()
+            }
+            Message::BackgroundFit(fit) => {
+                self.background_fit = fit;
+                Task::none()
+            }
+            Message::DataSaver(data_saver) => {
+                self.data_saver = data_saver;
+                Task::none()
+            }
+            Message::PhotoOrder(order) => {
+                self.photo_order = order;
+                Task::none()
+            }
+            Message::TextColor(color) => {
+                self.text_color = color;
+                Task::none()
+            }
+            Message::FontFamily(family) => {
+                self.font_family = family;
+                Task::none()
+            }
+            Message::ClockSize(size) => {
+                self.clock_size = size;
+                Task::none()
+            }
+            Message::RotationInterval(interval) => {
+                self.rotation_interval = interval;
+                Task::none()
+            }
+            Message::ForecastInterval(interval) => {
+                self.forecast_interval = interval;
+                Task::none()
+            }
+            Message::WeatherModel(model) => {
+                self.weather_model = model;
+                Task::none()
+            }
+            Message::TemperatureUnit(unit) => {
+                self.temperature_unit = unit;
+                Task::none()
+            }
+            Message::WindUnit(unit) => {
+                self.wind_unit = unit;
+                Task::none()
+            }
+            Message::PrecipitationUnit(unit) => {
+                self.precipitation_unit = unit;
+                Task::none()
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::BackgroundBlur(blur) => {
+                self.background_blur = blur;
+                Task::none()
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::TransparentWindow(transparent) => {
+                self.transparent_window = transparent;
+                Task::none()
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::StartFullscreen(fullscreen) => {
+                self.start_fullscreen = fullscreen;
                 Task::none()
             }
             Message::Location(location) => {
                 self.location = location;
-                Task::none()
+                self.location_fetch_error = None;
+
+                if self.location == WeatherLocation::Coordinates {
+                    self.resolve_place()
+                } else {
+                    Task::none()
+                }
             }
             Message::Name(name) => {
                 self.name = name;
@@ -158,7 +599,13 @@ impl Settings {
                 let meteo = self.meteo.clone();
                 let name = self.name.clone();
 
-                Task::future(async move { meteo.geocode(&name, None).await })
+                let opt = GeocodeOptions {
+                    count: Some(GEOCODE_RESULTS),
+                    language: geocode_language(),
+                    ..Default::default()
+                };
+
+                Task::future(async move { meteo.geocode(&name, Some(opt)).await })
                     .map(|r| Message::Geocode(r.map_err(|e| e.to_string())))
             }
             Message::Geocode(locations) => {
@@ -171,16 +618,12 @@ impl Settings {
                         self.location_results = res
                             .iter()
                             .map(|l| {
-                                let level1 = if let Some(admin1) = &l.admin1 {
-                                    format!(", {admin1}")
-                                } else {
-                                    String::new()
-                                };
+                                let (latitude, longitude) = l.coordinates();
 
                                 LocationRow {
-                                    name: format!("{}{level1}, {}", l.name, l.country),
-                                    latitude: l.latitude,
-                                    longitude: l.longitude,
+                                    name: l.display_name(),
+                                    latitude,
+                                    longitude,
                                 }
                             })
                             .collect()
@@ -189,19 +632,62 @@ impl Settings {
 
                 Task::none()
             }
+            Message::UseCurrentLocation => {
+                self.location_fetch_error = None;
+                let meteo = self.meteo.clone();
+
+                Task::future(async move {
+                    let (latitude, longitude) = geolocation::current().await?;
+
+                    // the coordinates are enough, the name is a nicety
+                    let name = match meteo.reverse_geocode(latitude, longitude).await {
+                        Ok(Some(location)) => location.display_name(),
+                        _ => format!("{latitude}, {longitude}"),
+                    };
+
+                    anyhow::Ok(LocationRow {
+                        name,
+                        latitude,
+                        longitude,
+                    })
+                })
+                .map(|r| Message::CurrentLocation(r.map_err(|e| e.to_string())))
+            }
+            Message::CurrentLocation(res) => match res {
+                Err(e) => {
+                    error!("failed to find current location: {e}");
+                    self.location_fetch_error = Some(e);
+                    Task::none()
+                }
+                Ok(loc) => Task::done(Message::LocationSelected(loc)),
+            },
             Message::LocationSelected(loc) => {
                 self.name = loc.name;
                 self.latitude = loc.latitude.to_string();
                 self.longitude = loc.longitude.to_string();
+                self.location_fetch_error = None;
 
                 Task::none()
             }
             Message::Latitude(latitude) => {
                 self.latitude = latitude;
+                self.resolved_place = None;
+                self.location_fetch_error = None;
                 Task::none()
             }
             Message::Longitude(longitude) => {
                 self.longitude = longitude;
+                self.resolved_place = None;
+                self.location_fetch_error = None;
+                Task::none()
+            }
+            Message::CoordinatesSubmitted => self.resolve_place(),
+            Message::PlaceResolved(coordinates, place) => {
+                // the coordinates may have been edited in the meantime
+                if self.coordinates() == Some(coordinates) {
+                    self.resolved_place = place;
+                }
+
                 Task::none()
             }
             #[cfg(not(target_arch = "wasm32"))]
@@ -228,25 +714,94 @@ impl Settings {
 
                 Task::none()
             }
-            Message::Save => {
-                let mut config = self.config.borrow_mut();
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::Export => {
+                if self.file_selector_open {
+                    return Task::none();
+                }
 
-                config.time_format = self.time_format.clone();
-                config.background_mode = self.background_mode;
-                config.background = self.background.clone();
-                config.unsplash_key = if self.unsplash_key.is_empty() {
-                    None
-                } else {
-                    Some(self.unsplash_key.clone())
-                };
+                self.file_selector_open = true;
+
+                let config = self.config.borrow().clone();
+                let file_task = AsyncFileDialog::new()
+                    .add_filter("json", &["json"])
+                    .set_file_name("fjordgard.json")
+                    .save_file();
+
+                Task::future(async move {
+                    match file_task.await {
+                        Some(file) => config.export_to(file.path()).await,
+                        None => Ok(()),
+                    }
+                })
+                .map(|r| Message::Exported(r.map_err(|e| e.to_string())))
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::Exported(res) => {
+                self.file_selector_open = false;
+
+                if let Err(e) = &res {
+                    error!("failed to export config: {e}");
+                }
+
+                self.transfer_error = res.err();
+                Task::none()
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::Import => {
+                if self.file_selector_open {
+                    return Task::none();
+                }
+
+                self.file_selector_open = true;
+
+                let file_task = AsyncFileDialog::new()
+                    .add_filter("json", &["json"])
+                    .pick_file();
+
+                Task::future(async move {
+                    match file_task.await {
+                        Some(file) => Config::import_from(file.path())
+                            .await
+                            .map(|c| Some(Box::new(c))),
+                        None => Ok(None),
+                    }
+                })
+                .map(|r| Message::Imported(r.map_err(|e| e.to_string())))
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Message::Imported(res) => {
+                self.file_selector_open = false;
 
-                match self.location {
-                    WeatherLocation::Disabled => config.location = None,
+                match res {
+                    Err(e) => {
+                        error!("failed to import config: {e}");
+                        self.transfer_error = Some(e);
+                        Task::none()
+                    }
+                    Ok(None) => Task::none(),
+                    Ok(Some(mut config)) => {
+                        config.mock = self.mock;
+                        *self.config.borrow_mut() = *config;
+                        // pick up the imported values in the form
+                        *self = Self::new(self.config.clone(), self.meteo.clone());
+
+                        Self::commit(self.config.borrow().clone())
+                    }
+                }
+            }
+            Message::Save => {
+                let location = match self.location {
+                    WeatherLocation::Disabled => None,
                     _ => {
-                        config.location = Some(config::Location {
-                            // this *should* be safe if we're at this point
-                            longitude: self.longitude.parse().unwrap(),
-                            latitude: self.latitude.parse().unwrap(),
+                        let Some((latitude, longitude)) = self.coordinates() else {
+                            self.save_error = Some(String::from("Invalid coordinates"));
+                            return Task::none();
+                        };
+
+                        Some(config::Location {
+                            longitude,
+                            latitude,
                             name: if self.location == WeatherLocation::LocationName {
                                 Some(self.name.clone())
                             } else {
@@ -254,19 +809,64 @@ impl Settings {
                             },
                         })
                     }
-                }
+                };
 
-                let cloned = config.clone();
+                self.save_error = None;
+                let mut config = self.config.borrow_mut();
+
+                config.time_format = match self.time_format {
+                    TimeFormat::TwelveHour => TimePreset::TwelveHour,
+                    TimeFormat::TwentyFourHour => TimePreset::TwentyFourHour,
+                    TimeFormat::Custom => TimePreset::Custom(self.custom_time_format.clone()),
+                };
+                config.background_mode = self.background_mode;
+                config.background = self.background.clone();
+                config.unsplash_key = if self.unsplash_key.is_empty() {
+                    None
+                } else {
+                    Some(self.unsplash_key.clone())
+                };
+                config.overlay_opacity = self.overlay_opacity;
+                config.theme = self.theme;
+                config.background_fit = self.background_fit;
+                config.data_saver = self.data_saver;
+                config.photo_order = self.photo_order;
+                config.font_family = if self.font_family.is_empty() {
+                    None
+                } else {
+                    Some(self.font_family.clone())
+                };
+                config.clock_size = self.clock_size;
+                config.rotation_interval_secs = self.rotation_interval.0;
+                config.forecast_interval_secs = self.forecast_interval.0;
+                config.weather_model = if self.weather_model == WeatherModel::BestMatch {
+                    None
+                } else {
+                    Some(self.weather_model.clone())
+                };
+                config.temperature_unit = self.temperature_unit;
+                config.wind_unit = self.wind_unit;
+                config.precipitation_unit = self.precipitation_unit;
+                config.text_color = if self.text_color.is_empty() {
+                    None
+                } else {
+                    Some(self.text_color.clone())
+                };
+                config.extra_timezones = self.extra_timezones.clone();
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    config.background_blur = self.background_blur;
+                    config.transparent_window = self.transparent_window;
+                    config.start_fullscreen = self.start_fullscreen;
+                }
+                config.location = location;
 
-                Task::batch([
-                    Task::done(Message::Committed),
-                    Task::future(async move { cloned.save().await })
-                        .map(|r| Message::Saved(r.map_err(|e| e.to_string()))),
-                ])
+                Self::commit(config.clone())
             }
             Message::Saved(res) => match res {
                 Err(e) => {
                     error!("failed to save config: {e}");
+                    self.save_error = Some(format!("Failed to save settings: {e}"));
                     Task::none()
                 }
                 Ok(()) => Task::none(),
@@ -275,7 +875,12 @@ impl Settings {
         }
     }
 
-    pub fn view(&self) -> Element<Message> {
+    /// The theme currently selected, which may not be saved yet.
+    pub fn theme(&self) -> Theme {
+        self.theme.theme()
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
         let (latitude, longitude, name) = match self.location {
             WeatherLocation::Disabled => (None, None, None),
             WeatherLocation::LocationName => (None, None, Some(Message::Name)),
@@ -288,7 +893,11 @@ impl Settings {
 
         let color_style = if (self.background_mode == BackgroundMode::Solid
             && Color::parse(&self.background).is_none())
-            || (self.background_mode == BackgroundMode::Unsplash && self.background.is_empty())
+            || (self.background_mode == BackgroundMode::Gradient
+                && parse_gradient(&self.background).is_none())
+            || (self.background_mode == BackgroundMode::Unsplash
+                && config::parse_collection_id(&self.background).is_none())
+            || (self.background_mode == BackgroundMode::UnsplashTopic && self.background.is_empty())
         {
             save_message = None;
             text_input_error
@@ -296,21 +905,49 @@ impl Settings {
             text_input::default
         };
 
-        let unsplash_style =
-            if self.background_mode == BackgroundMode::Unsplash && self.unsplash_key.is_empty() {
+        let text_color_style =
+            if !self.text_color.is_empty() && Color::parse(&self.text_color).is_none() {
                 save_message = None;
                 text_input_error
             } else {
                 text_input::default
             };
 
-        let unsplash_key = if self.background_mode == BackgroundMode::Unsplash {
+        let unsplash_style = if self.background_mode.is_unsplash()
+            && ((self.unsplash_key.is_empty() && !self.mock)
+                || matches!(self.unsplash_key_check, Some(KeyCheck::Invalid)))
+        {
+            save_message = None;
+            text_input_error
+        } else {
+            text_input::default
+        };
+
+        let test_unsplash_key = (self.background_mode.is_unsplash()
+            && !self.unsplash_key.is_empty()
+            && !matches!(self.unsplash_key_check, Some(KeyCheck::Testing)))
+        .then_some(Message::TestUnsplashKey);
+
+        let unsplash_key_check: Element<Message> = match &self.unsplash_key_check {
+            Some(KeyCheck::Valid) => text("✓").style(text::success).into(),
+            Some(KeyCheck::Failed(e)) => tooltip(
+                text("!").style(text::danger),
+                container(text(format!("Failed to test key: {e}")))
+                    .padding(5)
+                    .style(container::rounded_box),
+                tooltip::Position::Top,
+            )
+            .into(),
+            _ => column![].into(),
+        };
+
+        let unsplash_key = if self.background_mode.is_unsplash() {
             Some(Message::UnsplashKey)
         } else {
             None
         };
 
-        let latitude_style = if self.latitude.parse::<f64>().is_err()
+        let latitude_style = if self.parsed_latitude().is_none()
             && matches!(
                 self.location,
                 WeatherLocation::LocationName | WeatherLocation::Coordinates
@@ -321,7 +958,7 @@ impl Settings {
             text_input::default
         };
 
-        let longitude_style = if self.longitude.parse::<f64>().is_err()
+        let longitude_style = if self.parsed_longitude().is_none()
             && matches!(
                 self.location,
                 WeatherLocation::LocationName | WeatherLocation::Coordinates
@@ -361,6 +998,15 @@ impl Settings {
             }
         }
 
+        let resolved_place: Element<Message> = match &self.resolved_place {
+            Some(place) if self.location == WeatherLocation::Coordinates => row![
+                text("").width(Length::FillPortion(1)),
+                text(place).size(14).width(Length::FillPortion(2)),
+            ]
+            .into(),
+            _ => column![].into(),
+        };
+
         let mut results = column![];
 
         for res in self.location_results.iter() {
@@ -381,13 +1027,20 @@ impl Settings {
             text_input::default
         };
 
+        let current_location =
+            (self.location != WeatherLocation::Disabled).then_some(Message::UseCurrentLocation);
+
         let mut location_row: Element<Message> = row![
             text("Location").width(Length::FillPortion(1)),
-            text_input("", &self.name)
-                .width(Length::FillPortion(2))
-                .on_input_maybe(name)
-                .on_submit(Message::NameSubmitted)
-                .style(location_style)
+            row![
+                text_input("", &self.name)
+                    .on_input_maybe(name)
+                    .on_submit(Message::NameSubmitted)
+                    .style(location_style),
+                button("Use my location").on_press_maybe(current_location),
+            ]
+            .spacing(5)
+            .width(Length::FillPortion(2))
         ]
         .into();
 
@@ -402,69 +1055,302 @@ impl Settings {
             .into()
         };
 
-        scrollable(
-            container(
-                column![
-                    row![
-                        text("Time format").width(Length::FillPortion(1)),
-                        text_input("", &self.time_format)
-                            .width(Length::FillPortion(2))
-                            .on_input(Message::TimeFormat)
-                    ],
-                    row![
-                        text("Background mode").width(Length::FillPortion(1)),
-                        combo_box(
-                            &self.backgrounds,
-                            "",
-                            Some(&self.background_mode),
-                            Message::BackgroundMode
-                        )
-                        .width(Length::FillPortion(2))
-                    ],
-                    background_mode_row,
-                    row![
-                        text("Unsplash API Key").width(Length::FillPortion(1)),
-                        text_input("", &self.unsplash_key)
-                            .width(Length::FillPortion(2))
-                            .on_input_maybe(unsplash_key)
-                            .style(unsplash_style)
-                    ],
-                    row![
-                        text("Weather Location").width(Length::FillPortion(1)),
-                        combo_box(&self.locations, "", Some(&self.location), Message::Location)
-                            .width(Length::FillPortion(2))
-                    ],
-                    row![
-                        text("Latitude").width(Length::FillPortion(1)),
-                        text_input("", &self.latitude)
-                            .width(Length::FillPortion(2))
-                            .on_input_maybe(latitude)
-                            .style(latitude_style)
-                    ],
-                    row![
-                        text("Longitude").width(Length::FillPortion(1)),
-                        text_input("", &self.longitude)
-                            .width(Length::FillPortion(2))
-                            .on_input_maybe(longitude)
-                            .style(longitude_style)
-                    ],
-                    location_row,
-                    scrollable(results)
-                        .height(Length::Fixed(
-                            64.0 * (self.location_results.len().clamp(0, 1) as f32)
-                        ))
-                        .width(Length::Fill),
-                    row![
-                        button("Save").on_press_maybe(save_message),
-                        button("Close").on_press(Message::CloseSettings),
-                    ]
-                    .spacing(5)
+        let mut clock = column![
+            row![
+                text("Clock font").width(Length::FillPortion(1)),
+                text_input("Default", &self.font_family)
+                    .width(Length::FillPortion(2))
+                    .on_input(Message::FontFamily)
+            ],
+            row![
+                text(format!("Clock size ({})", self.clock_size)).width(Length::FillPortion(1)),
+                slider(50..=400, self.clock_size, Message::ClockSize)
+                    .step(10u16)
+                    .width(Length::FillPortion(2))
+            ],
+            row![
+                text("Time format").width(Length::FillPortion(1)),
+                combo_box(
+                    &self.time_formats,
+                    "",
+                    Some(&self.time_format),
+                    Message::TimeFormat
+                )
+                .width(Length::FillPortion(2))
+            ],
+        ]
+        .spacing(10);
+
+        if self.time_format == TimeFormat::Custom {
+            let items = parse_time_format(&self.custom_time_format);
+
+            let format_style = if items.is_none() {
+                save_message = None;
+                text_input_error
+            } else {
+                text_input::default
+            };
+
+            clock = clock.push(row![
+                text("Format string").width(Length::FillPortion(1)),
+                text_input("%-I:%M:%S", &self.custom_time_format)
+                    .width(Length::FillPortion(2))
+                    .on_input(Message::CustomTimeFormat)
+                    .style(format_style)
+            ]);
+
+            if let Some(items) = items {
+                let preview = Local::now().format_with_items(items.iter()).to_string();
+
+                clock = clock.push(row![
+                    text("").width(Length::FillPortion(1)),
+                    text(preview).size(14).width(Length::FillPortion(2)),
+                ]);
+            }
+        }
+
+        for (i, tz) in self.extra_timezones.iter().enumerate() {
+            let tz_style = if tz.parse::<Tz>().is_err() {
+                save_message = None;
+                text_input_error
+            } else {
+                text_input::default
+            };
+
+            clock = clock.push(row![
+                text("Time zone").width(Length::FillPortion(1)),
+                row![
+                    text_input("Europe/Oslo", tz)
+                        .on_input(move |name| Message::Timezone(i, name))
+                        .style(tz_style),
+                    button("Remove").on_press(Message::RemoveTimezone(i)),
                 ]
-                .spacing(10),
+                .spacing(5)
+                .width(Length::FillPortion(2))
+            ]);
+        }
+
+        clock = clock.push(button("Add time zone").on_press(Message::AddTimezone));
+
+        #[cfg_attr(target_arch = "wasm32", allow(unused_mut))]
+        let mut content = column![
+            clock,
+            row![
+                text("Background mode").width(Length::FillPortion(1)),
+                combo_box(
+                    &self.backgrounds,
+                    "",
+                    Some(&self.background_mode),
+                    Message::BackgroundMode
+                )
+                .width(Length::FillPortion(2))
+            ],
+            background_mode_row,
+            row![
+                text("Background fit").width(Length::FillPortion(1)),
+                combo_box(
+                    &self.fits,
+                    "",
+                    Some(&self.background_fit),
+                    Message::BackgroundFit
+                )
+                .width(Length::FillPortion(2))
+            ],
+            row![
+                text("Change wallpaper every").width(Length::FillPortion(1)),
+                combo_box(
+                    &self.rotation_intervals,
+                    "",
+                    Some(&self.rotation_interval),
+                    Message::RotationInterval
+                )
+                .width(Length::FillPortion(2))
+            ],
+            row![
+                text("Photo order").width(Length::FillPortion(1)),
+                combo_box(
+                    &self.photo_orders,
+                    "",
+                    Some(&self.photo_order),
+                    Message::PhotoOrder
+                )
+                .width(Length::FillPortion(2))
+            ],
+            checkbox(
+                "Data saver (smaller, compressed Unsplash photos)",
+                self.data_saver
             )
-            .padding(15),
-        )
-        .into()
+            .on_toggle(Message::DataSaver),
+            row![
+                text("Theme").width(Length::FillPortion(1)),
+                combo_box(&self.themes, "", Some(&self.theme), Message::Theme)
+                    .width(Length::FillPortion(2))
+            ],
+            row![
+                text("Text color").width(Length::FillPortion(1)),
+                text_input("Automatic", &self.text_color)
+                    .width(Length::FillPortion(2))
+                    .on_input(Message::TextColor)
+                    .style(text_color_style)
+            ],
+            row![
+                text("Overlay opacity").width(Length::FillPortion(1)),
+                slider(0.0..=1.0, self.overlay_opacity, Message::OverlayOpacity)
+                    .step(0.05)
+                    .width(Length::FillPortion(2))
+            ],
+            row![
+                text("Unsplash API Key").width(Length::FillPortion(1)),
+                row![
+                    text_input("", &self.unsplash_key)
+                        .on_input_maybe(unsplash_key)
+                        .style(unsplash_style),
+                    unsplash_key_check,
+                    button("Test").on_press_maybe(test_unsplash_key),
+                ]
+                .spacing(5)
+                .align_y(Alignment::Center)
+                .width(Length::FillPortion(2))
+            ],
+            row![
+                text("Weather Location").width(Length::FillPortion(1)),
+                combo_box(&self.locations, "", Some(&self.location), Message::Location)
+                    .width(Length::FillPortion(2))
+            ],
+            row![
+                text("Refresh weather every").width(Length::FillPortion(1)),
+                combo_box(
+                    &self.forecast_intervals,
+                    "",
+                    Some(&self.forecast_interval),
+                    Message::ForecastInterval
+                )
+                .width(Length::FillPortion(2))
+            ],
+            row![
+                text("Weather model").width(Length::FillPortion(1)),
+                combo_box(
+                    &self.weather_models,
+                    "",
+                    Some(&self.weather_model),
+                    Message::WeatherModel
+                )
+                .width(Length::FillPortion(2))
+            ],
+            row![
+                text("Temperature unit").width(Length::FillPortion(1)),
+                combo_box(
+                    &self.temperature_units,
+                    "",
+                    Some(&self.temperature_unit),
+                    Message::TemperatureUnit
+                )
+                .width(Length::FillPortion(2))
+            ],
+            row![
+                text("Wind speed unit").width(Length::FillPortion(1)),
+                combo_box(
+                    &self.wind_units,
+                    "",
+                    Some(&self.wind_unit),
+                    Message::WindUnit
+                )
+                .width(Length::FillPortion(2))
+            ],
+            row![
+                text("Precipitation unit").width(Length::FillPortion(1)),
+                combo_box(
+                    &self.precipitation_units,
+                    "",
+                    Some(&self.precipitation_unit),
+                    Message::PrecipitationUnit
+                )
+                .width(Length::FillPortion(2))
+            ],
+            row![
+                text("Latitude").width(Length::FillPortion(1)),
+                text_input("", &self.latitude)
+                    .width(Length::FillPortion(2))
+                    .on_input_maybe(latitude)
+                    .on_submit(Message::CoordinatesSubmitted)
+                    .style(latitude_style)
+            ],
+            row![
+                text("Longitude").width(Length::FillPortion(1)),
+                text_input("", &self.longitude)
+                    .width(Length::FillPortion(2))
+                    .on_input_maybe(longitude)
+                    .on_submit(Message::CoordinatesSubmitted)
+                    .style(longitude_style)
+            ],
+            resolved_place,
+            location_row,
+            scrollable(results)
+                .height(Length::Fixed(
+                    RESULT_HEIGHT * self.location_results.len().min(VISIBLE_RESULTS) as f32
+                ))
+                .width(Length::Fill),
+        ]
+        .spacing(10);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            content = content.push(row![
+                text("Background blur").width(Length::FillPortion(1)),
+                slider(
+                    0.0..=config::MAX_BACKGROUND_BLUR,
+                    self.background_blur,
+                    Message::BackgroundBlur
+                )
+                .step(1.0)
+                .width(Length::FillPortion(2))
+            ]);
+            content = content.push(
+                checkbox(
+                    "Transparent window (requires restart)",
+                    self.transparent_window,
+                )
+                .on_toggle(Message::TransparentWindow),
+            );
+            content = content.push(
+                checkbox("Start fullscreen (F11 to toggle)", self.start_fullscreen)
+                    .on_toggle(Message::StartFullscreen),
+            );
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            content = content.push(
+                row![
+                    button("Export settings").on_press(Message::Export),
+                    button("Import settings").on_press(Message::Import),
+                ]
+                .spacing(5),
+            );
+
+            if let Some(err) = &self.transfer_error {
+                content = content.push(text(err).style(text::danger));
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(notice) = &self.storage_notice {
+            content = content.push(text(notice).style(text::secondary));
+        }
+
+        if let Some(err) = &self.save_error {
+            content = content.push(text(err).style(text::danger));
+        }
+
+        content = content.push(
+            row![
+                button("Save").on_press_maybe(save_message),
+                button("Close").on_press(Message::CloseSettings),
+            ]
+            .spacing(5),
+        );
+
+        scrollable(container(content).padding(15)).into()
     }
 }
 
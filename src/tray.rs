@@ -0,0 +1,99 @@
+#[derive(Debug, Clone, Copy, PartialEq, strum::EnumString, strum::AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum Action {
+    ToggleWindow,
+    Next,
+    Pause,
+    Quit,
+}
+
+pub use imp::{Tray, events};
+
+/// `tray-icon` needs GTK on Linux, so the tray is only built for Windows and
+/// macOS.
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+mod imp {
+    use iced::futures::{SinkExt, Stream, StreamExt, channel::mpsc};
+    use log::warn;
+    use tray_icon::{
+        Icon, TrayIcon, TrayIconBuilder,
+        menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem},
+    };
+
+    use super::Action;
+
+    const ICON_SIZE: u32 = 32;
+
+    pub struct Tray {
+        _icon: TrayIcon,
+    }
+
+    impl Tray {
+        pub fn new() -> anyhow::Result<Self> {
+            let item =
+                |action: Action, text: &str| MenuItem::with_id(action.as_ref(), text, true, None);
+
+            let menu = Menu::new();
+            menu.append_items(&[
+                &item(Action::ToggleWindow, "Show/Hide"),
+                &item(Action::Next, "Next wallpaper"),
+                &item(Action::Pause, "Pause"),
+                &PredefinedMenuItem::separator(),
+                &item(Action::Quit, "Quit"),
+            ])?;
+
+            // plain white square, the clock has no logo
+            let rgba = vec![0xff; (ICON_SIZE * ICON_SIZE * 4) as usize];
+            let icon = Icon::from_rgba(rgba, ICON_SIZE, ICON_SIZE)?;
+
+            let icon = TrayIconBuilder::new()
+                .with_menu(Box::new(menu))
+                .with_tooltip("Fjordgard")
+                .with_icon(icon)
+                .build()?;
+
+            Ok(Self { _icon: icon })
+        }
+    }
+
+    /// Actions picked from the tray menu.
+    pub fn events() -> impl Stream<Item = Action> {
+        iced::stream::channel(10, |mut output| async move {
+            let (tx, mut rx) = mpsc::unbounded();
+
+            MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+                let _ = tx.unbounded_send(event);
+            }));
+
+            while let Some(event) = rx.next().await {
+                match event.id.as_ref().parse::<Action>() {
+                    Ok(action) => {
+                        if output.send(action).await.is_err() {
+                            return;
+                        }
+                    }
+                    Err(_) => warn!("unknown tray menu item {}", event.id.as_ref()),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+mod imp {
+    use iced::futures::{Stream, stream};
+
+    use super::Action;
+
+    pub struct Tray;
+
+    impl Tray {
+        pub fn new() -> anyhow::Result<Self> {
+            anyhow::bail!("system tray is not supported on this platform")
+        }
+    }
+
+    pub fn events() -> impl Stream<Item = Action> {
+        stream::pending()
+    }
+}